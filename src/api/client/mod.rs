@@ -13,7 +13,7 @@ use serde::de::DeserializeOwned;
 use tokio::time::sleep;
 
 use super::payload::{Payload, PayloadError};
-use crate::types::{Response, ResponseError};
+use crate::types::{Message, ReplyParameters, Response, ResponseError, SendMessage};
 
 #[cfg(test)]
 mod tests;
@@ -162,6 +162,32 @@ impl Client {
         Err(ExecuteError::TooManyRequests)
     }
 
+    /// Sends a text message split into several messages when it is too long.
+    ///
+    /// Each message after the first one is sent as a reply to the previous one.
+    /// See [`crate::types::Text::split`] for details about splitting.
+    ///
+    /// Returns the sent messages in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The message to send.
+    ///
+    /// # Notes
+    ///
+    /// A text using HTML or MarkdownV2 parse mode is parsed locally and sent with entities;
+    /// the message is sent as is when the markup is invalid or the legacy Markdown mode is used.
+    pub async fn send_message_thread(&self, method: SendMessage) -> Result<Vec<Message>, ExecuteError> {
+        let mut result: Vec<Message> = Vec::new();
+        for mut chunk in method.split() {
+            if let Some(last) = result.last() {
+                chunk = chunk.with_reply_parameters(ReplyParameters::new(last.id));
+            }
+            result.push(self.execute(chunk).await?);
+        }
+        Ok(result)
+    }
+
     async fn send_request<T>(&self, http_request: HttpRequestBuilder) -> Result<Response<T>, ExecuteError>
     where
         T: DeserializeOwned,
@@ -4,6 +4,8 @@ use log::debug;
 use reqwest::{Client as HttpClient, Method as HttpMethod, RequestBuilder as HttpRequestBuilder};
 use serde::ser::Serialize;
 use serde_json::Error as JsonError;
+#[cfg(feature = "webhook")]
+use serde_json::{Map as JsonMap, Value as JsonValue};
 
 #[cfg(test)]
 pub(crate) use self::tests::*;
@@ -52,6 +54,23 @@ impl Payload {
         }
     }
 
+    /// Converts the payload into a JSON object with a `method` field.
+    ///
+    /// Returns the payload back when it can not be represented as JSON (e.g. a multipart form).
+    #[cfg(feature = "webhook")]
+    pub(crate) fn into_json_method(self) -> Result<JsonValue, Self> {
+        let mut data = match &self.payload_data {
+            PayloadData::Json(Ok(data)) => match serde_json::from_str::<JsonMap<String, JsonValue>>(data) {
+                Ok(data) => data,
+                Err(_) => return Err(self),
+            },
+            PayloadData::Empty => JsonMap::new(),
+            _ => return Err(self),
+        };
+        data.insert(String::from("method"), JsonValue::String(self.url_path));
+        Ok(JsonValue::Object(data))
+    }
+
     pub(super) fn build_url(&self, base_url: &str, token: &str) -> String {
         format!("{}/bot{}/{}", base_url, token, self.url_path)
     }
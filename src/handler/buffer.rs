@@ -0,0 +1,222 @@
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+    time::Duration,
+};
+
+use log::{debug, error, warn};
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+    time::sleep,
+};
+
+use crate::{
+    handler::UpdateHandler,
+    types::{Integer, Update},
+};
+
+const DEFAULT_CAPACITY: usize = 1000;
+const DEFAULT_REORDER_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Drops duplicate updates and reorders updates by ID before passing them to a handler.
+///
+/// Telegram may redeliver a webhook update after a timeout,
+/// and concurrent webhook connections may deliver updates out of order.
+///
+/// When an update arrives ahead of the next expected ID,
+/// it is held until the missing updates arrive or the reorder timeout expires.
+/// Then updates are passed to the handler in ascending order of IDs, the same as in [`super::LongPoll`].
+///
+/// An update arriving after a newer one has already been passed
+/// is still handled, but in this case the order is not guaranteed.
+///
+/// Updates are handled one by one in a background task,
+/// so the handler finishes an update before it gets the next one.
+/// A panic in the handler is logged and does not stop handling of the next updates.
+pub struct UpdateBuffer<H> {
+    handler: Arc<H>,
+    reorder_timeout: Duration,
+    sender: OnceLock<UnboundedSender<Update>>,
+    state: Arc<Mutex<BufferState>>,
+}
+
+impl<H> UpdateBuffer<H> {
+    /// Creates a new `UpdateBuffer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Updates Handler.
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            reorder_timeout: DEFAULT_REORDER_TIMEOUT,
+            sender: OnceLock::new(),
+            state: Arc::new(Mutex::new(BufferState::new(DEFAULT_CAPACITY))),
+        }
+    }
+
+    /// Sets a new capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Number of recent update IDs to remember; default - 1000.
+    pub fn with_capacity(self, value: usize) -> Self {
+        self.lock_state().set_capacity(value);
+        self
+    }
+
+    /// Sets a new reorder timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How long to wait for missing updates; default - 500ms.
+    pub fn with_reorder_timeout(mut self, value: Duration) -> Self {
+        self.reorder_timeout = value;
+        self
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, BufferState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<H> UpdateBuffer<H>
+where
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    /// Passes updates to the worker task, which is spawned on first use.
+    ///
+    /// Must be called while the state is locked,
+    /// so that updates are queued in the same order they leave the buffer.
+    fn dispatch(&self, updates: Vec<Update>) {
+        let sender = self.sender.get_or_init(|| {
+            let (sender, mut receiver) = unbounded_channel::<Update>();
+            let handler = self.handler.clone();
+            spawn(async move {
+                while let Some(update) = receiver.recv().await {
+                    let update_id = update.id;
+                    let handler = handler.clone();
+                    // a separate task keeps the worker alive when the handler panics
+                    if let Err(err) = spawn(async move { handler.handle(update).await }).await {
+                        error!("Failed to handle update {}: {}", update_id, err);
+                    }
+                }
+            });
+            sender
+        });
+        for update in updates {
+            if sender.send(update).is_err() {
+                warn!("Update buffer worker has stopped");
+                break;
+            }
+        }
+    }
+}
+
+impl<H> UpdateHandler for UpdateBuffer<H>
+where
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    async fn handle(&self, update: Update) {
+        let update_id = update.id;
+        {
+            let mut state = self.lock_state();
+            if let Some(updates) = state.push(update) {
+                self.dispatch(updates);
+                return;
+            }
+        }
+        sleep(self.reorder_timeout).await;
+        let mut state = self.lock_state();
+        let updates = state.flush(update_id);
+        self.dispatch(updates);
+    }
+}
+
+struct BufferState {
+    capacity: usize,
+    last_id: Option<Integer>,
+    pending: BTreeMap<Integer, Update>,
+    recent_ids: HashSet<Integer>,
+    recent_ids_order: VecDeque<Integer>,
+}
+
+impl BufferState {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            last_id: None,
+            pending: BTreeMap::new(),
+            recent_ids: HashSet::new(),
+            recent_ids_order: VecDeque::new(),
+        }
+    }
+
+    fn set_capacity(&mut self, value: usize) {
+        self.capacity = value;
+        self.truncate_recent_ids();
+    }
+
+    /// Returns updates ready to be handled
+    /// or `None` when the update is held until the missing updates arrive.
+    fn push(&mut self, update: Update) -> Option<Vec<Update>> {
+        let update_id = update.id;
+        if !self.recent_ids.insert(update_id) {
+            debug!("Dropped a duplicate update: {}", update_id);
+            return Some(Vec::new());
+        }
+        self.recent_ids_order.push_back(update_id);
+        self.truncate_recent_ids();
+        match self.last_id {
+            Some(last_id) if update_id <= last_id => {
+                warn!("Received update {} after update {}", update_id, last_id);
+                Some(vec![update])
+            }
+            Some(last_id) if update_id > last_id + 1 => {
+                self.pending.insert(update_id, update);
+                None
+            }
+            _ => {
+                self.last_id = Some(update_id);
+                let mut result = vec![update];
+                result.extend(self.take_consecutive());
+                Some(result)
+            }
+        }
+    }
+
+    /// Returns held updates up to the given ID, when they are still pending.
+    fn flush(&mut self, update_id: Integer) -> Vec<Update> {
+        if !self.pending.contains_key(&update_id) {
+            return Vec::new();
+        }
+        let pending = self.pending.split_off(&(update_id + 1));
+        let mut result: Vec<Update> = std::mem::replace(&mut self.pending, pending).into_values().collect();
+        self.last_id = Some(update_id);
+        result.extend(self.take_consecutive());
+        result
+    }
+
+    fn take_consecutive(&mut self) -> Vec<Update> {
+        let mut result = Vec::new();
+        while let Some(last_id) = self.last_id {
+            match self.pending.remove(&(last_id + 1)) {
+                Some(update) => {
+                    self.last_id = Some(update.id);
+                    result.push(update);
+                }
+                None => break,
+            }
+        }
+        result
+    }
+
+    fn truncate_recent_ids(&mut self) {
+        while self.recent_ids_order.len() > self.capacity {
+            if let Some(update_id) = self.recent_ids_order.pop_front() {
+                self.recent_ids.remove(&update_id);
+            }
+        }
+    }
+}
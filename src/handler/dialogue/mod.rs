@@ -0,0 +1,352 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    marker::PhantomData,
+    sync::{Arc, Mutex as StdMutex},
+    time::Duration,
+};
+
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+pub use self::storage::*;
+use crate::{
+    handler::UpdateHandler,
+    types::{ChatPeerId, CommandParser, Integer, Update, UpdateType, UserPeerId},
+};
+
+mod storage;
+
+/// Identifies a dialogue.
+///
+/// A dialogue is held by a user in a chat,
+/// and in a forum topic when the update belongs to a topic.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct DialogueKey {
+    /// ID of the chat.
+    pub chat_id: ChatPeerId,
+    /// ID of the user.
+    pub user_id: UserPeerId,
+    /// ID of the message thread.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_thread_id: Option<Integer>,
+}
+
+impl DialogueKey {
+    /// Creates a new `DialogueKey`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_id` - ID of the chat.
+    /// * `user_id` - ID of the user.
+    pub fn new<A, B>(chat_id: A, user_id: B) -> Self
+    where
+        A: Into<ChatPeerId>,
+        B: Into<UserPeerId>,
+    {
+        Self {
+            chat_id: chat_id.into(),
+            user_id: user_id.into(),
+            message_thread_id: None,
+        }
+    }
+
+    /// Sets a new message thread ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - ID of the message thread.
+    pub fn with_message_thread_id(mut self, value: Integer) -> Self {
+        self.message_thread_id = Some(value);
+        self
+    }
+
+    /// Returns a key of the dialogue an update belongs to.
+    ///
+    /// Returns `None` when the update has no chat or no user.
+    ///
+    /// The thread is taken into account only for forum topic messages,
+    /// so that replies in a regular group share a dialogue with other messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update.
+    pub fn from_update(update: &Update) -> Option<Self> {
+        let key = Self::new(update.get_chat_id()?, update.get_user_id()?);
+        let message_thread_id = update
+            .get_message()
+            .filter(|x| x.is_topic_message == Some(true))
+            .and_then(|x| x.message_thread_id);
+        Some(match message_thread_id {
+            Some(message_thread_id) => key.with_message_thread_id(message_thread_id),
+            None => key,
+        })
+    }
+}
+
+/// Represents a result of handling an update in a dialogue.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueTransition<S> {
+    /// Moves the dialogue to the given state.
+    Next(S),
+    /// Keeps the current state.
+    ///
+    /// The timeout of the dialogue is not reset.
+    Stay,
+    /// Finishes the dialogue and removes its state.
+    Exit,
+}
+
+/// Represents a handler of updates in a dialogue.
+///
+/// The handler receives the current state of the dialogue
+/// and returns a transition to the next state.
+pub trait DialogueHandler<S> {
+    /// Handles an update.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The current state; default state when the dialogue is not started.
+    /// * `update` - The update.
+    fn handle(&self, state: S, update: Update) -> impl Future<Output = DialogueTransition<S>> + Send;
+
+    /// Called when a dialogue is cancelled with a cancel command.
+    ///
+    /// The state is removed before the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the cancelled dialogue.
+    /// * `update` - The update with the cancel command.
+    fn handle_cancel(&self, state: S, update: Update) -> impl Future<Output = ()> + Send {
+        let _ = (state, update);
+        async {}
+    }
+
+    /// Called when a dialogue has timed out.
+    ///
+    /// The state is removed before the call,
+    /// and then the update is handled in the default state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the expired dialogue.
+    /// * `update` - The update received after the timeout.
+    fn handle_timeout(&self, state: S, update: &Update) -> impl Future<Output = ()> + Send {
+        let _ = (state, update);
+        async {}
+    }
+}
+
+/// Runs dialogues over incoming updates.
+///
+/// States are user-defined types kept in a [`DialogueStorage`] by [`DialogueKey`].
+/// Updates without a chat or a user are skipped.
+/// Updates of the same dialogue are handled one at a time.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use tgbot::{
+///     handler::{Dialogue, DialogueHandler, DialogueTransition, MemoryDialogueStorage},
+///     types::Update,
+/// };
+///
+/// #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// enum Registration {
+///     #[default]
+///     Start,
+///     ReceiveName,
+///     ReceiveAge { name: String },
+/// }
+///
+/// struct Handler;
+///
+/// impl DialogueHandler<Registration> for Handler {
+///     async fn handle(&self, state: Registration, update: Update) -> DialogueTransition<Registration> {
+///         let text = update.get_message().and_then(|x| x.get_text()).map(|x| x.data.clone());
+///         match (state, text) {
+///             (Registration::Start, _) => DialogueTransition::Next(Registration::ReceiveName),
+///             (Registration::ReceiveName, Some(name)) => DialogueTransition::Next(Registration::ReceiveAge { name }),
+///             (Registration::ReceiveAge { name }, Some(age)) => {
+///                 println!("{} is {} years old", name, age);
+///                 DialogueTransition::Exit
+///             }
+///             _ => DialogueTransition::Stay,
+///         }
+///     }
+/// }
+///
+/// let dialogue = Dialogue::new(Handler, MemoryDialogueStorage::<Registration>::default())
+///     .with_cancel_command("cancel");
+/// ```
+pub struct Dialogue<H, S, T> {
+    handler: H,
+    storage: T,
+    cancel_commands: Vec<String>,
+    command_parser: CommandParser,
+    timeout: Option<Duration>,
+    locks: StdMutex<HashMap<DialogueKey, Arc<Mutex<()>>>>,
+    state_type: PhantomData<fn() -> S>,
+}
+
+impl<H, S, T> Dialogue<H, S, T> {
+    /// Creates a new `Dialogue`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Dialogue handler.
+    /// * `storage` - Storage of states.
+    pub fn new(handler: H, storage: T) -> Self
+    where
+        H: DialogueHandler<S>,
+        T: DialogueStorage<S>,
+    {
+        Self {
+            handler,
+            storage,
+            cancel_commands: Vec::new(),
+            command_parser: CommandParser::default(),
+            timeout: None,
+            locks: StdMutex::new(HashMap::new()),
+            state_type: PhantomData,
+        }
+    }
+
+    /// Adds a command cancelling an active dialogue.
+    ///
+    /// The command is recognized only in messages and business messages,
+    /// when there is no active dialogue, the command is passed to the handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The name of the command, e.g. `cancel`.
+    pub fn with_cancel_command<C>(mut self, value: C) -> Self
+    where
+        C: Into<String>,
+    {
+        let value = value.into();
+        self.cancel_commands.push(format!("/{}", value.trim_start_matches('/')));
+        self
+    }
+
+    /// Sets a new username of the bot.
+    ///
+    /// Cancel commands addressed to other bots are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The username of the bot.
+    pub fn with_bot_username<C>(mut self, value: C) -> Self
+    where
+        C: Into<String>,
+    {
+        self.command_parser = self.command_parser.with_bot_username(value);
+        self
+    }
+
+    /// Sets a new timeout.
+    ///
+    /// A dialogue without transitions during the timeout expires,
+    /// see [`DialogueHandler::handle_timeout`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The timeout; default - dialogues never expire.
+    pub fn with_timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Returns the storage of states.
+    ///
+    /// Allows to start or finish a dialogue outside of the handler.
+    pub fn get_storage(&self) -> &T {
+        &self.storage
+    }
+
+    fn get_lock(&self, key: DialogueKey) -> Arc<Mutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|err| err.into_inner());
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks.entry(key).or_default().clone()
+    }
+
+    fn is_expired(&self, record: &DialogueRecord<S>) -> bool {
+        match self.timeout {
+            Some(timeout) => record
+                .updated_at
+                .elapsed()
+                .map(|elapsed| elapsed > timeout)
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    fn is_cancel_command(&self, update: &Update) -> bool {
+        if self.cancel_commands.is_empty() {
+            return false;
+        }
+        // a message of a callback query is sent by the bot, not by the user
+        let message = match update.update_type {
+            UpdateType::Message(ref x) | UpdateType::BusinessMessage(ref x) => x,
+            _ => return false,
+        };
+        self.command_parser
+            .parse_name(message)
+            .is_some_and(|name| self.cancel_commands.contains(&name))
+    }
+}
+
+impl<H, S, T> Dialogue<H, S, T>
+where
+    H: DialogueHandler<S> + Sync,
+    S: Default + Send,
+    T: DialogueStorage<S> + Sync,
+{
+    async fn handle_dialogue(&self, key: DialogueKey, update: Update) -> Result<(), T::Error> {
+        let mut record = self.storage.get_state(key).await?;
+        if let Some(current) = record.take_if(|x| self.is_expired(x)) {
+            debug!("Dialogue {:?} has expired", key);
+            self.storage.remove_state(key).await?;
+            self.handler.handle_timeout(current.state, &update).await;
+        }
+        if let Some(current) = record.take_if(|_| self.is_cancel_command(&update)) {
+            debug!("Dialogue {:?} was cancelled", key);
+            self.storage.remove_state(key).await?;
+            self.handler.handle_cancel(current.state, update).await;
+            return Ok(());
+        }
+        let is_active = record.is_some();
+        let state = record.map(|x| x.state).unwrap_or_default();
+        match self.handler.handle(state, update).await {
+            DialogueTransition::Next(state) => self.storage.set_state(key, DialogueRecord::new(state)).await,
+            DialogueTransition::Stay => Ok(()),
+            DialogueTransition::Exit if is_active => self.storage.remove_state(key).await,
+            DialogueTransition::Exit => Ok(()),
+        }
+    }
+}
+
+impl<H, S, T> UpdateHandler for Dialogue<H, S, T>
+where
+    H: DialogueHandler<S> + Sync,
+    S: Default + Send,
+    T: DialogueStorage<S> + Sync,
+{
+    async fn handle(&self, update: Update) {
+        let key = match DialogueKey::from_update(&update) {
+            Some(key) => key,
+            None => {
+                debug!("Skipping update {} without a dialogue key", update.id);
+                return;
+            }
+        };
+        let lock = self.get_lock(key);
+        let _guard = lock.lock().await;
+        if let Err(err) = self.handle_dialogue(key, update).await {
+            error!("Dialogue {:?} storage error: {}", key, err);
+        }
+    }
+}
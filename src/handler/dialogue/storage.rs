@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    error::Error,
+    future::Future,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::DialogueKey;
+use crate::handler::json_file::{read_entries, write_entries, JsonFileStorageError};
+
+/// Represents a state of a dialogue saved in a storage.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DialogueRecord<S> {
+    /// The state of the dialogue.
+    pub state: S,
+    /// Time of the last transition.
+    pub updated_at: SystemTime,
+}
+
+impl<S> DialogueRecord<S> {
+    /// Creates a new `DialogueRecord` updated now.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the dialogue.
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            updated_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Represents a storage of dialogue states.
+pub trait DialogueStorage<S> {
+    /// An error returned by the storage.
+    type Error: Error + Send + 'static;
+
+    /// Returns a state of a dialogue.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    fn get_state(
+        &self,
+        key: DialogueKey,
+    ) -> impl Future<Output = Result<Option<DialogueRecord<S>>, Self::Error>> + Send;
+
+    /// Saves a state of a dialogue.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    /// * `record` - The state to save.
+    fn set_state(
+        &self,
+        key: DialogueKey,
+        record: DialogueRecord<S>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Removes a state of a dialogue.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    fn remove_state(&self, key: DialogueKey) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Keeps dialogue states in memory.
+///
+/// States are lost when the process exits.
+pub struct MemoryDialogueStorage<S> {
+    records: Mutex<HashMap<DialogueKey, DialogueRecord<S>>>,
+}
+
+impl<S> MemoryDialogueStorage<S> {
+    fn lock_records(&self) -> MutexGuard<'_, HashMap<DialogueKey, DialogueRecord<S>>> {
+        self.records.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<S> Default for MemoryDialogueStorage<S> {
+    fn default() -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> DialogueStorage<S> for MemoryDialogueStorage<S>
+where
+    S: Clone + Send,
+{
+    type Error = Infallible;
+
+    async fn get_state(&self, key: DialogueKey) -> Result<Option<DialogueRecord<S>>, Self::Error> {
+        Ok(self.lock_records().get(&key).cloned())
+    }
+
+    async fn set_state(&self, key: DialogueKey, record: DialogueRecord<S>) -> Result<(), Self::Error> {
+        self.lock_records().insert(key, record);
+        Ok(())
+    }
+
+    async fn remove_state(&self, key: DialogueKey) -> Result<(), Self::Error> {
+        self.lock_records().remove(&key);
+        Ok(())
+    }
+}
+
+/// Keeps dialogue states in a JSON file.
+///
+/// The whole file is read and written on every operation,
+/// so the storage is suitable for bots with a small number of active dialogues.
+/// The file is replaced atomically using a temporary file in the same directory.
+pub struct JsonFileDialogueStorage<S> {
+    path: PathBuf,
+    lock: AsyncMutex<()>,
+    state_type: PhantomData<fn() -> S>,
+}
+
+impl<S> JsonFileDialogueStorage<S> {
+    /// Creates a new `JsonFileDialogueStorage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file; created on the first write.
+    pub fn new<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            lock: AsyncMutex::new(()),
+            state_type: PhantomData,
+        }
+    }
+}
+
+impl<S> JsonFileDialogueStorage<S>
+where
+    S: DeserializeOwned + Serialize,
+{
+    async fn update<F>(&self, key: DialogueKey, f: F) -> Result<(), DialogueStorageError>
+    where
+        F: FnOnce(&mut Vec<JsonFileEntry<S>>),
+    {
+        let _guard = self.lock.lock().await;
+        let mut entries: Vec<JsonFileEntry<S>> = read_entries(&self.path).await?;
+        entries.retain(|x| x.key != key);
+        f(&mut entries);
+        write_entries(&self.path, &entries).await
+    }
+}
+
+impl<S> DialogueStorage<S> for JsonFileDialogueStorage<S>
+where
+    S: DeserializeOwned + Serialize + Send + Sync,
+{
+    type Error = DialogueStorageError;
+
+    async fn get_state(&self, key: DialogueKey) -> Result<Option<DialogueRecord<S>>, Self::Error> {
+        let _guard = self.lock.lock().await;
+        Ok(read_entries::<JsonFileEntry<S>>(&self.path)
+            .await?
+            .into_iter()
+            .find(|x| x.key == key)
+            .map(|x| x.record))
+    }
+
+    async fn set_state(&self, key: DialogueKey, record: DialogueRecord<S>) -> Result<(), Self::Error> {
+        self.update(key, |entries| entries.push(JsonFileEntry { key, record }))
+            .await
+    }
+
+    async fn remove_state(&self, key: DialogueKey) -> Result<(), Self::Error> {
+        self.update(key, |_| ()).await
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct JsonFileEntry<S> {
+    key: DialogueKey,
+    #[serde(flatten)]
+    record: DialogueRecord<S>,
+}
+
+/// Represents an error returned by [`JsonFileDialogueStorage`].
+pub type DialogueStorageError = JsonFileStorageError;
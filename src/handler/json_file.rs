@@ -0,0 +1,64 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Error as JsonError;
+use tokio::fs;
+
+/// Reads entries from a JSON file, a missing file contains no entries.
+pub(super) async fn read_entries<T>(path: &Path) -> Result<Vec<T>, JsonFileStorageError>
+where
+    T: DeserializeOwned,
+{
+    match fs::read(path).await {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(err) if err.kind() == IoErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Replaces a JSON file atomically using a temporary file in the same directory.
+pub(super) async fn write_entries<T>(path: &Path, entries: &[T]) -> Result<(), JsonFileStorageError>
+where
+    T: Serialize,
+{
+    let data = serde_json::to_vec(entries)?;
+    let mut tmp_path = path.to_path_buf().into_os_string();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, data).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+/// Represents an error returned by a storage keeping data in a JSON file.
+#[derive(Debug, derive_more::From)]
+pub enum JsonFileStorageError {
+    /// Failed to read or write the file.
+    Io(IoError),
+    /// Failed to serialize or deserialize data.
+    Json(JsonError),
+}
+
+impl Error for JsonFileStorageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::JsonFileStorageError::*;
+        Some(match self {
+            Io(err) => err,
+            Json(err) => err,
+        })
+    }
+}
+
+impl fmt::Display for JsonFileStorageError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::JsonFileStorageError::*;
+        match self {
+            Io(err) => write!(out, "storage IO error: {}", err),
+            Json(err) => write!(out, "can not serialize storage data: {}", err),
+        }
+    }
+}
@@ -1,18 +1,17 @@
 use std::{cmp::max, collections::HashSet, sync::Arc, time::Duration};
 
 use async_stream::stream;
-use futures_util::{pin_mut, stream::StreamExt};
-use log::error;
+use futures_util::stream::Stream;
+use log::{error, info, warn};
 use tokio::{
-    spawn,
     sync::mpsc::{channel, Receiver, Sender},
     time::sleep,
 };
 
 use crate::{
     api::{Client, ExecuteError},
-    handler::UpdateHandler,
-    types::{AllowedUpdate, GetUpdates, Integer},
+    handler::{source::handle_stream, UpdateHandler, UpdateSource},
+    types::{AllowedUpdate, DeleteWebhook, GetUpdates, GetWebhookInfo, Integer, Update},
 };
 
 const DEFAULT_LIMIT: Integer = 100;
@@ -57,10 +56,7 @@ impl<H> LongPoll<H> {
     }
 }
 
-impl<H> LongPoll<H>
-where
-    H: UpdateHandler + Send + Sync + 'static,
-{
+impl<H> LongPoll<H> {
     /// Returns a handle allowing control over the polling loop.
     #[must_use]
     pub fn get_handle(&self) -> LongPollHandle {
@@ -69,18 +65,24 @@ where
         }
     }
 
-    /// Starts the polling loop.
-    pub async fn run(self) {
+    /// Converts the polling loop into a stream of updates.
+    ///
+    /// The handler is not used, updates are yielded by the stream instead.
+    /// The stream ends when the loop is stopped using [`LongPollHandle`].
+    pub fn into_stream(self) -> impl Stream<Item = Update> + Send + 'static {
         let LongPollOptions {
             mut offset,
             limit,
             poll_timeout,
             error_timeout,
             allowed_updates,
+            delete_webhook,
+            drop_pending_updates,
         } = self.options;
-        let client = self.client.clone();
+        let client = self.client;
         let mut receiver = self.receiver;
-        let s = stream! {
+        stream! {
+            check_webhook(&client, delete_webhook, drop_pending_updates).await;
             loop {
                 if receiver.try_recv().is_ok() {
                     receiver.close();
@@ -93,7 +95,22 @@ where
                     .with_timeout(poll_timeout);
                 let updates = match client.execute(method).await {
                     Ok(updates) => updates,
+                    Err(err) if delete_webhook && is_webhook_conflict(&err) => {
+                        warn!("Can not get updates while a webhook is active");
+                        if let Err(err) = delete(&client, drop_pending_updates).await {
+                            error!("An error has occurred while deleting the webhook: {}", err);
+                        }
+                        // the webhook may be set again by another instance of the bot
+                        sleep(error_timeout).await;
+                        continue
+                    }
                     Err(err) => {
+                        if is_webhook_conflict(&err) {
+                            error!(
+                                "Can not get updates while a webhook is active, \
+                                use LongPollOptions::with_delete_webhook to delete it automatically"
+                            );
+                        }
                         error!("An error has occurred while getting updates: {}", err);
                         let error_timeout = get_error_timeout(err, error_timeout);
                         sleep(error_timeout).await;
@@ -105,15 +122,30 @@ where
                     yield update
                 }
             }
-        };
-        pin_mut!(s);
-        while let Some(update) = s.next().await {
-            let handler = self.handler.clone();
-            spawn(async move { handler.handle(update).await });
         }
     }
 }
 
+impl<H> LongPoll<H>
+where
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    /// Starts the polling loop.
+    pub async fn run(self) {
+        let handler = self.handler.clone();
+        handle_stream(self.into_stream(), handler).await
+    }
+}
+
+impl<H> UpdateSource for LongPoll<H>
+where
+    H: Send + Sync + 'static,
+{
+    fn into_stream(self) -> impl Stream<Item = Update> + Send + 'static {
+        LongPoll::into_stream(self)
+    }
+}
+
 /// Allows to control a polling loop.
 pub struct LongPollHandle {
     sender: Sender<()>,
@@ -126,6 +158,46 @@ impl LongPollHandle {
     }
 }
 
+async fn check_webhook(client: &Client, delete_webhook: bool, drop_pending_updates: Option<bool>) {
+    let url = match client.execute(GetWebhookInfo).await {
+        Ok(webhook_info) => webhook_info.url,
+        Err(err) => {
+            error!("An error has occurred while getting webhook info: {}", err);
+            return;
+        }
+    };
+    if url.is_empty() {
+        return;
+    }
+    if !delete_webhook {
+        warn!(
+            "A webhook is active, updates can not be received until it is deleted: {}",
+            url
+        );
+        return;
+    }
+    if let Err(err) = delete(client, drop_pending_updates).await {
+        error!("An error has occurred while deleting the webhook: {}", err);
+    }
+}
+
+async fn delete(client: &Client, drop_pending_updates: Option<bool>) -> Result<(), ExecuteError> {
+    let mut method = DeleteWebhook::default();
+    if let Some(value) = drop_pending_updates {
+        method = method.with_drop_pending_updates(value);
+    }
+    client.execute(method).await?;
+    info!("Webhook has been deleted");
+    Ok(())
+}
+
+fn is_webhook_conflict(err: &ExecuteError) -> bool {
+    match err {
+        ExecuteError::Response(err) => err.error_code() == Some(409) && err.description().contains("webhook"),
+        _ => false,
+    }
+}
+
 fn get_error_timeout(err: ExecuteError, default_timeout: Duration) -> Duration {
     if let ExecuteError::Response(err) = err {
         err.retry_after().map(Duration::from_secs).unwrap_or(default_timeout)
@@ -142,6 +214,8 @@ pub struct LongPollOptions {
     poll_timeout: Duration,
     error_timeout: Duration,
     allowed_updates: HashSet<AllowedUpdate>,
+    delete_webhook: bool,
+    drop_pending_updates: Option<bool>,
 }
 
 impl LongPollOptions {
@@ -155,6 +229,30 @@ impl LongPollOptions {
         self
     }
 
+    /// Sets a new value for the `delete_webhook` flag.
+    ///
+    /// Polling is not possible while a webhook is active,
+    /// the webhook is checked using [`GetWebhookInfo`] when polling starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Indicates whether to delete an active webhook
+    ///   instead of waiting until it is deleted; default - false.
+    pub fn with_delete_webhook(mut self, value: bool) -> Self {
+        self.delete_webhook = value;
+        self
+    }
+
+    /// Sets a new value for the `drop_pending_updates` flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Indicates whether to drop all pending updates when an active webhook is deleted.
+    pub fn with_drop_pending_updates(mut self, value: bool) -> Self {
+        self.drop_pending_updates = Some(value);
+        self
+    }
+
     /// Sets a new error timeout.
     ///
     /// # Arguments
@@ -198,6 +296,8 @@ impl Default for LongPollOptions {
             poll_timeout: DEFAULT_POLL_TIMEOUT,
             error_timeout: DEFAULT_ERROR_TIMEOUT,
             allowed_updates: HashSet::new(),
+            delete_webhook: false,
+            drop_pending_updates: None,
         }
     }
 }
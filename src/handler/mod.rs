@@ -1,11 +1,25 @@
 use std::future::Future;
 
-pub use self::longpoll::*;
 #[cfg(feature = "webhook")]
 pub use self::webhook::*;
+pub use self::{
+    buffer::*,
+    dialogue::*,
+    json_file::JsonFileStorageError,
+    longpoll::*,
+    router::*,
+    session::*,
+    source::*,
+};
 use crate::types::Update;
 
+mod buffer;
+mod dialogue;
+mod json_file;
 mod longpoll;
+mod router;
+mod session;
+mod source;
 
 #[cfg(feature = "webhook")]
 mod webhook;
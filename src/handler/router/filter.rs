@@ -0,0 +1,539 @@
+use std::{collections::HashSet, future::Future};
+
+use log::error;
+
+use crate::{
+    api::Client,
+    types::{
+        AllowedUpdate,
+        CallbackQuery,
+        Chat,
+        ChatMember,
+        CommandParser,
+        GetChatMember,
+        Message,
+        MessageData,
+        Update,
+        UpdateType,
+    },
+};
+
+pub(super) const ALL_UPDATES: [AllowedUpdate; 23] = [
+    AllowedUpdate::BotStatus,
+    AllowedUpdate::BusinessConnection,
+    AllowedUpdate::BusinessMessage,
+    AllowedUpdate::CallbackQuery,
+    AllowedUpdate::ChannelPost,
+    AllowedUpdate::ChatBoostRemoved,
+    AllowedUpdate::ChatBoostUpdated,
+    AllowedUpdate::ChatJoinRequest,
+    AllowedUpdate::ChosenInlineResult,
+    AllowedUpdate::DeletedBusinessMessages,
+    AllowedUpdate::EditedBusinessMessage,
+    AllowedUpdate::EditedChannelPost,
+    AllowedUpdate::EditedMessage,
+    AllowedUpdate::InlineQuery,
+    AllowedUpdate::Message,
+    AllowedUpdate::MessageReaction,
+    AllowedUpdate::MessageReactionCount,
+    AllowedUpdate::Poll,
+    AllowedUpdate::PollAnswer,
+    AllowedUpdate::PreCheckoutQuery,
+    AllowedUpdate::PurchasedPaidMedia,
+    AllowedUpdate::ShippingQuery,
+    AllowedUpdate::UserStatus,
+];
+
+const MESSAGE_UPDATES: [AllowedUpdate; 6] = [
+    AllowedUpdate::BusinessMessage,
+    AllowedUpdate::ChannelPost,
+    AllowedUpdate::EditedBusinessMessage,
+    AllowedUpdate::EditedChannelPost,
+    AllowedUpdate::EditedMessage,
+    AllowedUpdate::Message,
+];
+
+/// Represents a filter for a route of [`super::Router`].
+pub trait UpdateFilter {
+    /// Checks whether an update matches the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update to check.
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send;
+
+    /// Returns types of updates the filter can match.
+    ///
+    /// `None` means that the filter does not restrict update types.
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        None
+    }
+
+    /// Creates a filter which matches when both filters match.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Another filter.
+    fn and<T>(self, other: T) -> AndFilter<Self, T>
+    where
+        Self: Sized,
+        T: UpdateFilter,
+    {
+        AndFilter(self, other)
+    }
+
+    /// Creates a filter which matches when any of filters match.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Another filter.
+    fn or<T>(self, other: T) -> OrFilter<Self, T>
+    where
+        Self: Sized,
+        T: UpdateFilter,
+    {
+        OrFilter(self, other)
+    }
+
+    /// Creates a filter which matches when the filter does not match.
+    fn not(self) -> NotFilter<Self>
+    where
+        Self: Sized,
+    {
+        NotFilter(self)
+    }
+}
+
+/// Matches when both filters match.
+#[derive(Clone, Debug)]
+pub struct AndFilter<A, B>(A, B);
+
+impl<A, B> UpdateFilter for AndFilter<A, B>
+where
+    A: UpdateFilter,
+    B: UpdateFilter,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let a = self.0.matches(update);
+        let b = self.1.matches(update);
+        async move { a.await && b.await }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        match (self.0.get_allowed_updates(), self.1.get_allowed_updates()) {
+            (Some(a), Some(b)) => Some(a.intersection(&b).copied().collect()),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Matches when any of filters match.
+#[derive(Clone, Debug)]
+pub struct OrFilter<A, B>(A, B);
+
+impl<A, B> UpdateFilter for OrFilter<A, B>
+where
+    A: UpdateFilter,
+    B: UpdateFilter,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let a = self.0.matches(update);
+        let b = self.1.matches(update);
+        async move { a.await || b.await }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        match (self.0.get_allowed_updates(), self.1.get_allowed_updates()) {
+            (Some(a), Some(b)) => Some(a.union(&b).copied().collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Matches when the filter does not match.
+#[derive(Clone, Debug)]
+pub struct NotFilter<T>(T);
+
+impl<T> UpdateFilter for NotFilter<T>
+where
+    T: UpdateFilter,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let f = self.0.matches(update);
+        async move { !f.await }
+    }
+}
+
+/// Matches an update using a function.
+#[derive(Clone, Debug)]
+pub struct FnFilter<F>(F);
+
+impl<F> FnFilter<F>
+where
+    F: Fn(&Update) -> bool,
+{
+    /// Creates a new `FnFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function returning `true` when an update matches.
+    pub fn new(f: F) -> Self {
+        Self(f)
+    }
+}
+
+impl<F> UpdateFilter for FnFilter<F>
+where
+    F: Fn(&Update) -> bool,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = (self.0)(update);
+        async move { result }
+    }
+}
+
+/// Matches updates of the given types.
+#[derive(Clone, Debug)]
+pub struct UpdateKindFilter {
+    kinds: HashSet<AllowedUpdate>,
+}
+
+impl UpdateKindFilter {
+    /// Creates a new `UpdateKindFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kinds` - Types of updates to match.
+    pub fn new<T>(kinds: T) -> Self
+    where
+        T: IntoIterator<Item = AllowedUpdate>,
+    {
+        Self {
+            kinds: kinds.into_iter().collect(),
+        }
+    }
+}
+
+impl UpdateFilter for UpdateKindFilter {
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = get_update_kind(&update.update_type).is_some_and(|x| self.kinds.contains(&x));
+        async move { result }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        Some(self.kinds.clone())
+    }
+}
+
+/// Represents a type of a chat.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChatType {
+    /// A channel chat.
+    Channel,
+    /// A group chat.
+    Group,
+    /// A private chat.
+    Private,
+    /// A supergroup chat.
+    Supergroup,
+}
+
+impl From<&Chat> for ChatType {
+    fn from(value: &Chat) -> Self {
+        match value {
+            Chat::Channel(_) => Self::Channel,
+            Chat::Group(_) => Self::Group,
+            Chat::Private(_) => Self::Private,
+            Chat::Supergroup(_) => Self::Supergroup,
+        }
+    }
+}
+
+/// Matches updates from chats of the given types.
+#[derive(Clone, Debug)]
+pub struct ChatTypeFilter {
+    types: HashSet<ChatType>,
+}
+
+impl ChatTypeFilter {
+    /// Creates a new `ChatTypeFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `types` - Types of chats to match.
+    pub fn new<T>(types: T) -> Self
+    where
+        T: IntoIterator<Item = ChatType>,
+    {
+        Self {
+            types: types.into_iter().collect(),
+        }
+    }
+}
+
+impl UpdateFilter for ChatTypeFilter {
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = update
+            .get_chat()
+            .is_some_and(|x| self.types.contains(&ChatType::from(x)));
+        async move { result }
+    }
+}
+
+/// Matches messages where the first command has the given name.
+///
+/// Without a bot username, commands addressed to any bot are matched,
+/// use [`CommandFilter::with_bot_username`] to skip commands addressed to other bots in groups.
+#[derive(Clone, Debug)]
+pub struct CommandFilter {
+    name: String,
+    parser: CommandParser,
+}
+
+impl CommandFilter {
+    /// Creates a new `CommandFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the command with or without leading slash.
+    pub fn new<T>(name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self {
+            name: format!("/{}", name.as_ref().trim_start_matches('/')),
+            parser: CommandParser::default(),
+        }
+    }
+
+    /// Sets a new username of the bot.
+    ///
+    /// Commands addressed to other bots (e.g. `/start@other_bot`) are skipped,
+    /// see [`CommandParser::with_bot_username`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The username of the bot, see [`crate::types::Bot::username`].
+    pub fn with_bot_username<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.parser = self.parser.with_bot_username(value);
+        self
+    }
+}
+
+impl UpdateFilter for CommandFilter {
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = get_message(update)
+            .and_then(|x| self.parser.parse_name(x))
+            .is_some_and(|x| x == self.name);
+        async move { result }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        Some(HashSet::from(MESSAGE_UPDATES))
+    }
+}
+
+/// Matches messages with a text matching a function.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::handler::TextFilter;
+///
+/// let filter = TextFilter::new(|x| x.starts_with("hello"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextFilter<F> {
+    f: F,
+}
+
+impl<F> TextFilter<F>
+where
+    F: Fn(&str) -> bool,
+{
+    /// Creates a new `TextFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function returning `true` when a text matches, e.g. a regular expression check.
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F> UpdateFilter for TextFilter<F>
+where
+    F: Fn(&str) -> bool,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = get_message(update)
+            .and_then(|x| x.get_text())
+            .is_some_and(|x| (self.f)(&x.data));
+        async move { result }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        Some(HashSet::from(MESSAGE_UPDATES))
+    }
+}
+
+/// Matches callback queries with data starting with the given prefix.
+#[derive(Clone, Debug)]
+pub struct CallbackDataFilter {
+    prefix: String,
+}
+
+impl CallbackDataFilter {
+    /// Creates a new `CallbackDataFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Prefix of the callback data.
+    pub fn new<T>(prefix: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { prefix: prefix.into() }
+    }
+}
+
+impl UpdateFilter for CallbackDataFilter {
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = match update.update_type {
+            UpdateType::CallbackQuery(CallbackQuery {
+                data: Some(ref data), ..
+            }) => data.starts_with(&self.prefix),
+            _ => false,
+        };
+        async move { result }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        Some(HashSet::from([AllowedUpdate::CallbackQuery]))
+    }
+}
+
+/// Matches messages with data matching a function.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{handler::MessageDataFilter, types::MessageData};
+///
+/// let filter = MessageDataFilter::new(|x| matches!(x, MessageData::Photo { .. }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageDataFilter<F> {
+    f: F,
+}
+
+impl<F> MessageDataFilter<F>
+where
+    F: Fn(&MessageData) -> bool,
+{
+    /// Creates a new `MessageDataFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function returning `true` when data matches.
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F> UpdateFilter for MessageDataFilter<F>
+where
+    F: Fn(&MessageData) -> bool,
+{
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let result = get_message(update).is_some_and(|x| (self.f)(&x.data));
+        async move { result }
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        Some(HashSet::from(MESSAGE_UPDATES))
+    }
+}
+
+/// Matches updates sent by an administrator of a chat.
+///
+/// The filter calls [`GetChatMember`] for every update with a chat and a user.
+#[derive(Clone, Debug)]
+pub struct AdminFilter {
+    client: Client,
+}
+
+impl AdminFilter {
+    /// Creates a new `AdminFilter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Telegram Bot API Client.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl UpdateFilter for AdminFilter {
+    fn matches(&self, update: &Update) -> impl Future<Output = bool> + Send {
+        let ids = update.get_chat_id().zip(update.get_user_id());
+        let client = self.client.clone();
+        async move {
+            let (chat_id, user_id) = match ids {
+                Some(ids) => ids,
+                None => return false,
+            };
+            match client.execute(GetChatMember::new(chat_id, user_id.into())).await {
+                Ok(member) => matches!(member, ChatMember::Administrator(_) | ChatMember::Creator(_)),
+                Err(err) => {
+                    error!("An error has occurred while getting a chat member: {}", err);
+                    false
+                }
+            }
+        }
+    }
+}
+
+fn get_message(update: &Update) -> Option<&Message> {
+    match update.update_type {
+        UpdateType::BusinessMessage(ref x)
+        | UpdateType::ChannelPost(ref x)
+        | UpdateType::EditedBusinessMessage(ref x)
+        | UpdateType::EditedChannelPost(ref x)
+        | UpdateType::EditedMessage(ref x)
+        | UpdateType::Message(ref x) => Some(x),
+        _ => None,
+    }
+}
+
+fn get_update_kind(update_type: &UpdateType) -> Option<AllowedUpdate> {
+    Some(match update_type {
+        UpdateType::BotStatus(_) => AllowedUpdate::BotStatus,
+        UpdateType::BusinessConnection(_) => AllowedUpdate::BusinessConnection,
+        UpdateType::BusinessMessage(_) => AllowedUpdate::BusinessMessage,
+        UpdateType::CallbackQuery(_) => AllowedUpdate::CallbackQuery,
+        UpdateType::ChannelPost(_) => AllowedUpdate::ChannelPost,
+        UpdateType::ChatBoostRemoved(_) => AllowedUpdate::ChatBoostRemoved,
+        UpdateType::ChatBoostUpdated(_) => AllowedUpdate::ChatBoostUpdated,
+        UpdateType::ChatJoinRequest(_) => AllowedUpdate::ChatJoinRequest,
+        UpdateType::ChosenInlineResult(_) => AllowedUpdate::ChosenInlineResult,
+        UpdateType::DeletedBusinessMessages(_) => AllowedUpdate::DeletedBusinessMessages,
+        UpdateType::EditedBusinessMessage(_) => AllowedUpdate::EditedBusinessMessage,
+        UpdateType::EditedChannelPost(_) => AllowedUpdate::EditedChannelPost,
+        UpdateType::EditedMessage(_) => AllowedUpdate::EditedMessage,
+        UpdateType::InlineQuery(_) => AllowedUpdate::InlineQuery,
+        UpdateType::Message(_) => AllowedUpdate::Message,
+        UpdateType::MessageReaction(_) => AllowedUpdate::MessageReaction,
+        UpdateType::MessageReactionCount(_) => AllowedUpdate::MessageReactionCount,
+        UpdateType::Poll(_) => AllowedUpdate::Poll,
+        UpdateType::PollAnswer(_) => AllowedUpdate::PollAnswer,
+        UpdateType::PreCheckoutQuery(_) => AllowedUpdate::PreCheckoutQuery,
+        UpdateType::PurchasedPaidMedia(_) => AllowedUpdate::PurchasedPaidMedia,
+        UpdateType::ShippingQuery(_) => AllowedUpdate::ShippingQuery,
+        UpdateType::UserStatus(_) => AllowedUpdate::UserStatus,
+        UpdateType::Unknown(_) => return None,
+    })
+}
@@ -0,0 +1,184 @@
+use std::{collections::HashSet, future::Future, pin::Pin};
+
+pub use self::filter::*;
+use crate::{
+    handler::UpdateHandler,
+    types::{AllowedUpdate, Update},
+};
+
+mod filter;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Dispatches updates to handlers using filters.
+///
+/// Routes are checked in the order they were added.
+/// A route added with [`Router::with_route`] stops routing when its filter matches,
+/// a route added with [`Router::with_fallthrough_route`] passes the update to the next routes.
+/// The fallback handler is called when an update is not handled by a route which stops routing.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{
+///     handler::{ChatTypeFilter, ChatType, CommandFilter, Router, UpdateFilter, UpdateHandler},
+///     types::Update,
+/// };
+///
+/// struct Start;
+///
+/// impl UpdateHandler for Start {
+///     async fn handle(&self, update: Update) {
+///         println!("Start: {:?}", update);
+///     }
+/// }
+///
+/// struct Log;
+///
+/// impl UpdateHandler for Log {
+///     async fn handle(&self, update: Update) {
+///         println!("Update: {:?}", update.id);
+///     }
+/// }
+///
+/// let router = Router::default()
+///     .with_fallthrough_route(ChatTypeFilter::new([ChatType::Private]), Log)
+///     .with_route(CommandFilter::new("start"), Start);
+/// let allowed_updates = router.get_allowed_updates();
+/// ```
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+    fallback: Option<Box<dyn DynUpdateHandler>>,
+}
+
+impl Router {
+    /// Adds a route which stops routing when the filter matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter to check an update.
+    /// * `handler` - The handler to call when the filter matches.
+    pub fn with_route<F, H>(self, filter: F, handler: H) -> Self
+    where
+        F: UpdateFilter + Send + Sync + 'static,
+        H: UpdateHandler + Send + Sync + 'static,
+    {
+        self.add_route(filter, handler, false)
+    }
+
+    /// Adds a route which passes an update to the next routes when the filter matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter to check an update.
+    /// * `handler` - The handler to call when the filter matches.
+    pub fn with_fallthrough_route<F, H>(self, filter: F, handler: H) -> Self
+    where
+        F: UpdateFilter + Send + Sync + 'static,
+        H: UpdateHandler + Send + Sync + 'static,
+    {
+        self.add_route(filter, handler, true)
+    }
+
+    /// Sets a new fallback handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to call when an update is not handled by a route which stops routing.
+    pub fn with_fallback<H>(mut self, handler: H) -> Self
+    where
+        H: UpdateHandler + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Returns types of updates required by the routes.
+    ///
+    /// Use the result in [`super::LongPollOptions`] or [`crate::types::SetWebhook`]
+    /// to receive only updates you can handle.
+    ///
+    /// The fallback handler is not taken into account.
+    /// A route with a filter which does not restrict update types requires all of them.
+    pub fn get_allowed_updates(&self) -> HashSet<AllowedUpdate> {
+        let mut result = HashSet::new();
+        for route in &self.routes {
+            match route.filter.get_allowed_updates() {
+                Some(allowed_updates) => result.extend(allowed_updates),
+                None => return HashSet::from(ALL_UPDATES),
+            }
+        }
+        result
+    }
+
+    fn add_route<F, H>(mut self, filter: F, handler: H, fallthrough: bool) -> Self
+    where
+        F: UpdateFilter + Send + Sync + 'static,
+        H: UpdateHandler + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            filter: Box::new(filter),
+            handler: Box::new(handler),
+            fallthrough,
+        });
+        self
+    }
+}
+
+impl UpdateHandler for Router {
+    async fn handle(&self, update: Update) {
+        for route in &self.routes {
+            if !route.filter.matches(&update).await {
+                continue;
+            }
+            if route.fallthrough {
+                route.handler.handle(update.clone()).await;
+            } else {
+                route.handler.handle(update).await;
+                return;
+            }
+        }
+        if let Some(ref fallback) = self.fallback {
+            fallback.handle(update).await;
+        }
+    }
+}
+
+struct Route {
+    filter: Box<dyn DynUpdateFilter>,
+    handler: Box<dyn DynUpdateHandler>,
+    fallthrough: bool,
+}
+
+trait DynUpdateHandler: Send + Sync {
+    fn handle(&self, update: Update) -> BoxFuture<'_, ()>;
+}
+
+impl<T> DynUpdateHandler for T
+where
+    T: UpdateHandler + Send + Sync,
+{
+    fn handle(&self, update: Update) -> BoxFuture<'_, ()> {
+        Box::pin(UpdateHandler::handle(self, update))
+    }
+}
+
+trait DynUpdateFilter: Send + Sync {
+    fn matches<'a>(&'a self, update: &'a Update) -> BoxFuture<'a, bool>;
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>>;
+}
+
+impl<T> DynUpdateFilter for T
+where
+    T: UpdateFilter + Send + Sync,
+{
+    fn matches<'a>(&'a self, update: &'a Update) -> BoxFuture<'a, bool> {
+        Box::pin(UpdateFilter::matches(self, update))
+    }
+
+    fn get_allowed_updates(&self) -> Option<HashSet<AllowedUpdate>> {
+        UpdateFilter::get_allowed_updates(self)
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Error as JsonError, Value as JsonValue};
+
+pub use self::storage::*;
+use crate::{
+    handler::UpdateHandler,
+    types::{ChatPeerId, Update, UserPeerId},
+};
+
+mod storage;
+
+const DEFAULT_MAX_ATTEMPTS: usize = 5;
+
+/// Identifies a session.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKey {
+    /// A session of a chat.
+    Chat(ChatPeerId),
+    /// A session of a user.
+    User(UserPeerId),
+}
+
+impl From<ChatPeerId> for SessionKey {
+    fn from(value: ChatPeerId) -> Self {
+        Self::Chat(value)
+    }
+}
+
+impl From<UserPeerId> for SessionKey {
+    fn from(value: UserPeerId) -> Self {
+        Self::User(value)
+    }
+}
+
+/// Represents data of a session loaded from a storage.
+///
+/// Changes are applied to the storage using [`SessionManager::save`].
+#[derive(Clone, Debug)]
+pub struct Session {
+    key: SessionKey,
+    value: Option<JsonValue>,
+    version: u64,
+    is_changed: bool,
+}
+
+impl Session {
+    fn new(key: SessionKey, entry: Option<SessionEntry>) -> Self {
+        let (value, version) = match entry {
+            Some(entry) => (entry.value, entry.version),
+            None => (None, 0),
+        };
+        Self {
+            key,
+            value,
+            version,
+            is_changed: false,
+        }
+    }
+
+    /// Returns the key of the session.
+    pub fn get_key(&self) -> SessionKey {
+        self.key
+    }
+
+    /// Returns the version of the loaded data; 0 when the session has never been saved.
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    /// Indicates whether the data was changed after the session had been loaded.
+    pub fn is_changed(&self) -> bool {
+        self.is_changed
+    }
+
+    /// Returns the data of the session.
+    pub fn get<V>(&self) -> Result<Option<V>, JsonError>
+    where
+        V: DeserializeOwned,
+    {
+        self.value.clone().map(serde_json::from_value).transpose()
+    }
+
+    /// Sets new data of the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The data.
+    pub fn set<V>(&mut self, value: &V) -> Result<(), JsonError>
+    where
+        V: Serialize,
+    {
+        self.value = Some(serde_json::to_value(value)?);
+        self.is_changed = true;
+        Ok(())
+    }
+
+    /// Removes the data of the session.
+    pub fn remove(&mut self) {
+        self.value = None;
+        self.is_changed = true;
+    }
+}
+
+/// Provides typed access to sessions kept in a [`SessionStorage`].
+///
+/// Sessions use optimistic concurrency:
+/// a session is saved only when it was not changed by someone else after it had been loaded.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{
+///     handler::{MemorySessionStorage, SessionManager},
+///     types::UserPeerId,
+/// };
+///
+/// # async fn example() {
+/// let sessions = SessionManager::new(MemorySessionStorage::default());
+/// let user_id = UserPeerId::from(1);
+/// let counter: Option<u32> = sessions
+///     .update(user_id, |value: Option<u32>| Some(value.unwrap_or(0) + 1))
+///     .await
+///     .unwrap();
+/// assert_eq!(counter, Some(1));
+/// # }
+/// ```
+pub struct SessionManager<T> {
+    storage: Arc<T>,
+    max_attempts: usize,
+    ttl: Option<Duration>,
+}
+
+impl<T> Clone for SessionManager<T> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            max_attempts: self.max_attempts,
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl<T> SessionManager<T>
+where
+    T: SessionStorage,
+{
+    /// Creates a new `SessionManager`.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - Storage of sessions.
+    pub fn new(storage: T) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            ttl: None,
+        }
+    }
+
+    /// Sets a new number of attempts for [`SessionManager::update`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Number of attempts to apply a change when sessions are modified concurrently; default - 5.
+    pub fn with_max_attempts(mut self, value: usize) -> Self {
+        self.max_attempts = value.max(1);
+        self
+    }
+
+    /// Sets a new TTL.
+    ///
+    /// A session expires when it is not saved during the TTL.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The TTL; default - sessions never expire.
+    pub fn with_ttl(mut self, value: Duration) -> Self {
+        self.ttl = Some(value);
+        self
+    }
+
+    /// Returns the storage of sessions.
+    pub fn get_storage(&self) -> &T {
+        &self.storage
+    }
+
+    /// Loads a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    pub async fn load<K>(&self, key: K) -> Result<Session, SessionError>
+    where
+        K: Into<SessionKey>,
+    {
+        let key = key.into();
+        let entry = self.storage.load(key).await.map_err(SessionError::storage)?;
+        Ok(Session::new(key, entry))
+    }
+
+    /// Saves a changed session.
+    ///
+    /// Fails with [`SessionError::Conflict`] when the session was changed after it had been loaded.
+    /// A session expired after it had been loaded is not considered changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to save.
+    pub async fn save(&self, session: &mut Session) -> Result<(), SessionError> {
+        if !session.is_changed {
+            return Ok(());
+        }
+        let version = session.version + 1;
+        let entry = SessionEntry {
+            value: session.value.clone(),
+            version,
+            expires_at: session.value.as_ref().and(self.ttl).map(|ttl| SystemTime::now() + ttl),
+        };
+        let is_stored = self
+            .storage
+            .store(session.key, session.version, entry)
+            .await
+            .map_err(SessionError::storage)?;
+        if !is_stored {
+            return Err(SessionError::Conflict(session.key));
+        }
+        session.version = version;
+        session.is_changed = false;
+        Ok(())
+    }
+
+    /// Returns the data of a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    pub async fn get<K, V>(&self, key: K) -> Result<Option<V>, SessionError>
+    where
+        K: Into<SessionKey>,
+        V: DeserializeOwned,
+    {
+        Ok(self.load(key).await?.get()?)
+    }
+
+    /// Changes the data of a session.
+    ///
+    /// The function is called again with fresh data when the session is modified concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    /// * `f` - Receives the current data and returns new data; `None` removes the session.
+    pub async fn update<K, V, F>(&self, key: K, mut f: F) -> Result<Option<V>, SessionError>
+    where
+        K: Into<SessionKey>,
+        V: DeserializeOwned + Serialize,
+        F: FnMut(Option<V>) -> Option<V>,
+    {
+        let key = key.into();
+        let mut attempt = 1;
+        loop {
+            let mut session = self.load(key).await?;
+            let value = f(session.get()?);
+            match value {
+                Some(ref value) => session.set(value)?,
+                None => session.remove(),
+            }
+            match self.save(&mut session).await {
+                Err(SessionError::Conflict(_)) if attempt < self.max_attempts => attempt += 1,
+                Err(err) => return Err(err),
+                Ok(()) => return Ok(value),
+            }
+        }
+    }
+
+    /// Removes a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    pub async fn remove<K>(&self, key: K) -> Result<(), SessionError>
+    where
+        K: Into<SessionKey>,
+    {
+        self.update(key, |_: Option<JsonValue>| None).await.map(|_| ())
+    }
+}
+
+/// Represents an error occurred when loading or saving a session.
+#[derive(Debug)]
+pub enum SessionError {
+    /// The session was changed after it had been loaded.
+    Conflict(SessionKey),
+    /// Failed to serialize or deserialize session data.
+    Json(JsonError),
+    /// The storage returned an error.
+    Storage(Box<dyn Error + Send + Sync>),
+}
+
+impl SessionError {
+    fn storage<E>(err: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self::Storage(Box::new(err))
+    }
+}
+
+impl From<JsonError> for SessionError {
+    fn from(err: JsonError) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl Error for SessionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::SessionError::*;
+        match self {
+            Conflict(_) => None,
+            Json(err) => Some(err),
+            Storage(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::SessionError::*;
+        match self {
+            Conflict(key) => write!(out, "session {:?} was changed concurrently", key),
+            Json(err) => write!(out, "can not serialize session data: {}", err),
+            Storage(err) => write!(out, "session storage error: {}", err),
+        }
+    }
+}
+
+/// Represents sessions of the user and the chat of an update.
+#[derive(Clone, Debug)]
+pub struct UpdateSessions {
+    chat: Option<Session>,
+    user: Option<Session>,
+}
+
+impl UpdateSessions {
+    /// Returns the session of the chat.
+    ///
+    /// Returns `None` when the update has no chat.
+    pub fn get_chat_session(&mut self) -> Option<&mut Session> {
+        self.chat.as_mut()
+    }
+
+    /// Returns the session of the user.
+    ///
+    /// Returns `None` when the update has no user.
+    pub fn get_user_session(&mut self) -> Option<&mut Session> {
+        self.user.as_mut()
+    }
+}
+
+/// Represents a handler of updates which uses sessions.
+pub trait SessionUpdateHandler {
+    /// Handles an update.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update.
+    /// * `sessions` - Sessions of the user and the chat; changes are saved after the call.
+    fn handle(&self, update: Update, sessions: &mut UpdateSessions) -> impl Future<Output = ()> + Send;
+}
+
+/// Loads sessions of the user and the chat before handling an update and saves them after.
+///
+/// An update is not handled when sessions can not be loaded.
+/// Changes are not saved when a session was changed concurrently,
+/// use [`SessionManager::update`] in the handler to apply such changes.
+pub struct SessionHandler<H, T> {
+    handler: H,
+    manager: SessionManager<T>,
+}
+
+impl<H, T> SessionHandler<H, T> {
+    /// Creates a new `SessionHandler`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Updates handler.
+    /// * `manager` - Session manager.
+    pub fn new(handler: H, manager: SessionManager<T>) -> Self {
+        Self { handler, manager }
+    }
+
+    /// Returns the session manager.
+    pub fn get_manager(&self) -> &SessionManager<T> {
+        &self.manager
+    }
+}
+
+impl<H, T> SessionHandler<H, T>
+where
+    T: SessionStorage,
+{
+    async fn load_session<K>(&self, key: Option<K>) -> Result<Option<Session>, SessionError>
+    where
+        K: Into<SessionKey>,
+    {
+        Ok(match key {
+            Some(key) => Some(self.manager.load(key).await?),
+            None => None,
+        })
+    }
+
+    async fn load_sessions(&self, update: &Update) -> Result<UpdateSessions, SessionError> {
+        Ok(UpdateSessions {
+            chat: self.load_session(update.get_chat_id()).await?,
+            user: self.load_session(update.get_user_id()).await?,
+        })
+    }
+
+    async fn save_sessions(&self, sessions: UpdateSessions) {
+        for mut session in [sessions.chat, sessions.user].into_iter().flatten() {
+            if let Err(err) = self.manager.save(&mut session).await {
+                error!("Failed to save session: {}", err);
+            }
+        }
+    }
+}
+
+impl<H, T> UpdateHandler for SessionHandler<H, T>
+where
+    H: SessionUpdateHandler + Sync,
+    T: SessionStorage + Send + Sync,
+{
+    async fn handle(&self, update: Update) {
+        let mut sessions = match self.load_sessions(&update).await {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                error!("Failed to load sessions for update {}: {}", update.id, err);
+                return;
+            }
+        };
+        self.handler.handle(update, &mut sessions).await;
+        self.save_sessions(sessions).await;
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    error::Error,
+    future::Future,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::SessionKey;
+use crate::handler::json_file::{read_entries, write_entries, JsonFileStorageError};
+
+/// Represents session data saved in a storage.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SessionEntry {
+    /// The data of the session; `None` when the session is removed or expired.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_value",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value: Option<JsonValue>,
+    /// The version of the entry, starts with 1 and increases on every save,
+    /// including removal of the session.
+    pub version: u64,
+    /// Time after which the session is considered missing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<SystemTime>,
+}
+
+impl SessionEntry {
+    /// Indicates whether the session is expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|x| x <= SystemTime::now()).unwrap_or(false)
+    }
+
+    /// Removes the data of an expired session, keeping the version.
+    fn clear_expired(&mut self) {
+        if self.is_expired() {
+            self.value = None;
+            self.expires_at = None;
+        }
+    }
+}
+
+// keeps a `null` value, which is a valid data of a session
+fn deserialize_value<'de, D>(deserializer: D) -> Result<Option<JsonValue>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    JsonValue::deserialize(deserializer).map(Some)
+}
+
+/// Represents a storage of sessions.
+///
+/// An entry of a removed or expired session is kept without a value,
+/// so that versions of a session never repeat.
+pub trait SessionStorage {
+    /// An error returned by the storage.
+    type Error: Error + Send + Sync + 'static;
+
+    /// Returns an entry of a session.
+    ///
+    /// An expired session is returned without a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    fn load(&self, key: SessionKey) -> impl Future<Output = Result<Option<SessionEntry>, Self::Error>> + Send;
+
+    /// Replaces an entry of a session when the version of the stored entry equals the given one.
+    ///
+    /// Returns `false` when the version does not match.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the session.
+    /// * `version` - Expected version of the stored entry; 0 when there is no entry.
+    /// * `entry` - The new entry; an entry without a value removes the session.
+    fn store(
+        &self,
+        key: SessionKey,
+        version: u64,
+        entry: SessionEntry,
+    ) -> impl Future<Output = Result<bool, Self::Error>> + Send;
+}
+
+fn get_version(entry: Option<&SessionEntry>) -> u64 {
+    entry.map(|x| x.version).unwrap_or(0)
+}
+
+/// Keeps sessions in memory.
+///
+/// Sessions are lost when the process exits.
+#[derive(Default)]
+pub struct MemorySessionStorage {
+    entries: Mutex<HashMap<SessionKey, SessionEntry>>,
+}
+
+impl MemorySessionStorage {
+    fn lock_entries(&self) -> MutexGuard<'_, HashMap<SessionKey, SessionEntry>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl SessionStorage for MemorySessionStorage {
+    type Error = Infallible;
+
+    async fn load(&self, key: SessionKey) -> Result<Option<SessionEntry>, Self::Error> {
+        Ok(self.lock_entries().get(&key).cloned().map(|mut x| {
+            x.clear_expired();
+            x
+        }))
+    }
+
+    async fn store(&self, key: SessionKey, version: u64, entry: SessionEntry) -> Result<bool, Self::Error> {
+        let mut entries = self.lock_entries();
+        if get_version(entries.get(&key)) != version {
+            return Ok(false);
+        }
+        entries.values_mut().for_each(SessionEntry::clear_expired);
+        entries.insert(key, entry);
+        Ok(true)
+    }
+}
+
+/// Keeps sessions in a JSON file.
+///
+/// The whole file is read and written on every operation,
+/// so the storage is suitable for bots with a small number of sessions.
+/// Data of expired sessions is removed from the file on write.
+pub struct JsonFileSessionStorage {
+    path: PathBuf,
+    lock: AsyncMutex<()>,
+}
+
+impl JsonFileSessionStorage {
+    /// Creates a new `JsonFileSessionStorage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file; created on the first write.
+    pub fn new<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            lock: AsyncMutex::new(()),
+        }
+    }
+}
+
+impl SessionStorage for JsonFileSessionStorage {
+    type Error = JsonFileStorageError;
+
+    async fn load(&self, key: SessionKey) -> Result<Option<SessionEntry>, Self::Error> {
+        let _guard = self.lock.lock().await;
+        Ok(read_entries::<JsonFileEntry>(&self.path)
+            .await?
+            .into_iter()
+            .find(|x| x.key == key)
+            .map(|mut x| {
+                x.entry.clear_expired();
+                x.entry
+            }))
+    }
+
+    async fn store(&self, key: SessionKey, version: u64, entry: SessionEntry) -> Result<bool, Self::Error> {
+        let _guard = self.lock.lock().await;
+        let mut entries: Vec<JsonFileEntry> = read_entries(&self.path).await?;
+        if get_version(entries.iter().find(|x| x.key == key).map(|x| &x.entry)) != version {
+            return Ok(false);
+        }
+        entries.retain(|x| x.key != key);
+        entries.iter_mut().for_each(|x| x.entry.clear_expired());
+        entries.push(JsonFileEntry { key, entry });
+        write_entries(&self.path, &entries).await?;
+        Ok(true)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct JsonFileEntry {
+    key: SessionKey,
+    #[serde(flatten)]
+    entry: SessionEntry,
+}
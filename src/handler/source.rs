@@ -0,0 +1,54 @@
+use std::{future::Future, sync::Arc};
+
+use futures_util::{pin_mut, stream::Stream, StreamExt};
+use tokio::spawn;
+
+use crate::{handler::UpdateHandler, types::Update};
+
+/// Represents a source of incoming updates.
+///
+/// Allows to process updates from [`super::LongPoll`] and [`super::WebhookServer`] in the same way,
+/// e.g. to use long polling in development and webhooks in production.
+///
+/// Any stream of updates is a source,
+/// so you can apply stream combinators to a source before running a handler.
+pub trait UpdateSource {
+    /// Converts the source into a stream of updates.
+    fn into_stream(self) -> impl Stream<Item = Update> + Send + 'static;
+
+    /// Runs a handler over the source until the stream ends.
+    ///
+    /// Each update is handled in a separate task.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Updates Handler.
+    fn run_handler<H>(self, handler: H) -> impl Future<Output = ()> + Send
+    where
+        Self: Sized,
+        H: UpdateHandler + Send + Sync + 'static,
+    {
+        handle_stream(self.into_stream(), Arc::new(handler))
+    }
+}
+
+impl<S> UpdateSource for S
+where
+    S: Stream<Item = Update> + Send + 'static,
+{
+    fn into_stream(self) -> impl Stream<Item = Update> + Send + 'static {
+        self
+    }
+}
+
+pub(super) async fn handle_stream<S, H>(stream: S, handler: Arc<H>)
+where
+    S: Stream<Item = Update>,
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    pin_mut!(stream);
+    while let Some(update) = stream.next().await {
+        let handler = handler.clone();
+        spawn(async move { handler.handle(update).await });
+    }
+}
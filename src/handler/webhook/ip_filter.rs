@@ -0,0 +1,204 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnet::{IpNet, Ipv4Net};
+use log::warn;
+
+const FORWARDED_HEADER: &str = "Forwarded";
+const X_FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
+const TELEGRAM_NETWORKS: [(Ipv4Addr, u8); 2] = [
+    (Ipv4Addr::new(149, 154, 160, 0), 20),
+    (Ipv4Addr::new(91, 108, 4, 0), 22),
+];
+
+/// Allows to accept webhook requests only from the given networks.
+///
+/// By default, only requests from the Telegram networks
+/// (`149.154.160.0/20` and `91.108.4.0/22`) are accepted.
+///
+/// When a request comes from a trusted proxy, the client address is taken
+/// from the `Forwarded` or `X-Forwarded-For` header.
+/// A request is rejected when the header can not be parsed:
+/// it is not valid UTF-8, contains an invalid address
+/// or a `Forwarded` element without the `for` parameter.
+///
+/// Rejected requests are logged and counted; clones of a filter share the counter.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+#[derive(Clone, Debug)]
+pub struct WebhookIpFilter {
+    allowed_networks: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+    rejected_requests: Arc<AtomicU64>,
+}
+
+impl WebhookIpFilter {
+    /// Creates a new `WebhookIpFilter` with the given allowed networks.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed_networks` - Networks to accept requests from.
+    pub fn new<T>(allowed_networks: T) -> Self
+    where
+        T: IntoIterator<Item = IpNet>,
+    {
+        Self {
+            allowed_networks: allowed_networks.into_iter().collect(),
+            trusted_proxies: Vec::new(),
+            rejected_requests: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Adds an allowed network.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Network to accept requests from.
+    pub fn with_allowed_network(mut self, value: IpNet) -> Self {
+        self.allowed_networks.push(value);
+        self
+    }
+
+    /// Adds a trusted proxy.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Network of the proxy.
+    pub fn with_trusted_proxy(mut self, value: IpNet) -> Self {
+        self.trusted_proxies.push(value);
+        self
+    }
+
+    /// Returns the number of rejected requests.
+    pub fn rejected_requests(&self) -> u64 {
+        self.rejected_requests.load(Ordering::Relaxed)
+    }
+
+    fn is_allowed(&self, peer_address: IpAddr, headers: &HeaderMap) -> Result<(), Option<IpAddr>> {
+        let client_address = self.get_client_address(peer_address, headers).ok_or(None)?;
+        if self.allowed_networks.iter().any(|x| x.contains(&client_address)) {
+            Ok(())
+        } else {
+            Err(Some(client_address))
+        }
+    }
+
+    fn get_client_address(&self, peer_address: IpAddr, headers: &HeaderMap) -> Option<IpAddr> {
+        if !self.is_trusted_proxy(peer_address) {
+            return Some(peer_address);
+        }
+        let chain = match get_forwarded_chain(headers) {
+            Some(chain) => chain?,
+            None => return Some(peer_address),
+        };
+        chain
+            .iter()
+            .rev()
+            .find(|x| !self.is_trusted_proxy(**x))
+            .or_else(|| chain.first())
+            .copied()
+    }
+
+    fn is_trusted_proxy(&self, address: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|x| x.contains(&address))
+    }
+}
+
+impl Default for WebhookIpFilter {
+    fn default() -> Self {
+        Self::new(
+            TELEGRAM_NETWORKS
+                .into_iter()
+                .filter_map(|(address, prefix_len)| Ipv4Net::new(address, prefix_len).ok())
+                .map(IpNet::from),
+        )
+    }
+}
+
+type ParseChain = fn(&str) -> Option<Vec<IpAddr>>;
+
+/// Returns a list of forwarded addresses from the client to the last proxy.
+///
+/// The outer `None` means that headers are absent,
+/// the inner one means that they can not be parsed.
+fn get_forwarded_chain(headers: &HeaderMap) -> Option<Option<Vec<IpAddr>>> {
+    let parsers: [(&str, ParseChain); 2] = [
+        (FORWARDED_HEADER, parse_forwarded),
+        (X_FORWARDED_FOR_HEADER, parse_forwarded_for),
+    ];
+    for (name, parse) in parsers {
+        let mut values = headers.get_all(name).iter().peekable();
+        if values.peek().is_none() {
+            continue;
+        }
+        let mut result = Vec::new();
+        for value in values {
+            match value.to_str().ok().and_then(parse) {
+                Some(addresses) => result.extend(addresses),
+                None => return Some(None),
+            }
+        }
+        return Some(Some(result));
+    }
+    None
+}
+
+fn parse_forwarded(value: &str) -> Option<Vec<IpAddr>> {
+    value
+        .split(',')
+        .map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                name.trim().eq_ignore_ascii_case("for").then_some(value)
+            })
+        })
+        .map(|x| parse_node(x?.trim().trim_matches('"')))
+        .collect()
+}
+
+fn parse_forwarded_for(value: &str) -> Option<Vec<IpAddr>> {
+    value.split(',').map(|x| parse_node(x.trim())).collect()
+}
+
+fn parse_node(value: &str) -> Option<IpAddr> {
+    value
+        .parse::<IpAddr>()
+        .or_else(|_| value.parse::<SocketAddr>().map(|x| x.ip()))
+        .or_else(|_| value.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>())
+        .ok()
+        .map(|x| x.to_canonical())
+}
+
+pub(super) async fn verify_ip_address(
+    State(filter): State<WebhookIpFilter>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let result = match connect_info {
+        Some(ConnectInfo(peer_address)) => filter.is_allowed(peer_address.ip().to_canonical(), request.headers()),
+        None => Err(None),
+    };
+    match result {
+        Ok(()) => next.run(request).await,
+        Err(client_address) => {
+            filter.rejected_requests.fetch_add(1, Ordering::Relaxed);
+            match client_address {
+                Some(client_address) => warn!("Rejected a webhook request from {}", client_address),
+                None => warn!("Rejected a webhook request from an unknown address"),
+            }
+            StatusCode::FORBIDDEN.into_response()
+        }
+    }
+}
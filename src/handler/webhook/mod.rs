@@ -0,0 +1,566 @@
+use std::{convert::Infallible, error::Error, fmt, future::Future, hint::black_box, net::SocketAddr, sync::Arc};
+
+use async_stream::stream;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Json, Request, State},
+    http::StatusCode,
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    routing::{post, MethodRouter},
+    Error as AxumError,
+    Extension,
+    Router,
+};
+use bytes::Bytes;
+use futures_util::stream::Stream;
+pub use ipnet;
+use log::{debug, error, warn};
+use rand::Rng;
+use serde_json::{Error as JsonError, Value as JsonValue};
+use tokio::{
+    net::TcpListener,
+    spawn,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+#[cfg(feature = "webhook-tls")]
+pub use self::tls::*;
+pub use self::{ip_filter::*, registration::*};
+use crate::{
+    api::{Client, Method, Payload},
+    handler::{UpdateHandler, UpdateSource},
+    types::Update,
+};
+
+mod ip_filter;
+mod registration;
+#[cfg(feature = "webhook-tls")]
+mod tls;
+
+const DEFAULT_ADDRESS: ([u8; 4], u16) = ([127, 0, 0, 1], 8080);
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+const SECRET_TOKEN_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789_-";
+const SECRET_TOKEN_LENGTH: usize = 64;
+const STREAM_CAPACITY: usize = 100;
+
+type UpdateErrorHandler = Arc<dyn Fn(WebhookUpdateError) + Send + Sync>;
+
+/// Represents a simple webhook server for handling incoming updates from the Telegram Bot API.
+///
+/// The server responds with `200 OK` to every request passed authorization,
+/// even if the request does not contain a valid update.
+/// Otherwise Telegram would keep redelivering such an update
+/// and hold back all updates after it.
+/// Use [`WebhookServer::with_update_error_handler`] to get notified about invalid updates.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+pub struct WebhookServer {
+    path: String,
+    route: MethodRouter,
+    address: SocketAddr,
+    body_limit: usize,
+    ip_filter: Option<WebhookIpFilter>,
+    registration: Option<WebhookRegistration>,
+    secret_token: Option<Arc<str>>,
+    #[cfg(feature = "webhook-tls")]
+    tls_config: Option<WebhookTlsConfig>,
+    update_error_handler: UpdateErrorHandler,
+    sender: Sender<()>,
+    receiver: Receiver<()>,
+}
+
+impl WebhookServer {
+    /// Creates a new `WebhookServer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where the webhook server will receive incoming updates.
+    /// * `handler` - The handler for processing updates.
+    pub fn new<A, B>(path: A, handler: B) -> Self
+    where
+        A: AsRef<str>,
+        B: UpdateHandler + Send + Sync + 'static,
+    {
+        let route = post(handle_update::<B>).layer(Extension(Arc::new(handler)));
+        Self::with_route(path, route)
+    }
+
+    /// Creates a new `WebhookServer` with a handler able to reply to webhook requests.
+    ///
+    /// A reply returned by the handler is sent in the body of the webhook response,
+    /// which saves a request to the Telegram Bot API.
+    /// Replies which can not be sent this way (e.g. file uploads)
+    /// are executed using the given client.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where the webhook server will receive incoming updates.
+    /// * `handler` - The handler for processing updates.
+    /// * `client` - The client for executing replies which can not be sent in a response.
+    pub fn with_reply_handler<A, B>(path: A, handler: B, client: Client) -> Self
+    where
+        A: AsRef<str>,
+        B: WebhookUpdateHandler + Send + Sync + 'static,
+    {
+        let route = post(handle_update_with_reply::<B>)
+            .layer::<_, Infallible>(Extension(Arc::new(handler)))
+            .layer(Extension(client));
+        Self::with_route(path, route)
+    }
+
+    fn with_route<T>(path: T, route: MethodRouter) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let (sender, receiver) = channel(1);
+        Self {
+            path: String::from(path.as_ref()),
+            route,
+            address: SocketAddr::from(DEFAULT_ADDRESS),
+            body_limit: DEFAULT_BODY_LIMIT,
+            ip_filter: None,
+            registration: None,
+            secret_token: None,
+            #[cfg(feature = "webhook-tls")]
+            tls_config: None,
+            update_error_handler: Arc::new(|err| error!("Failed to read an incoming update: {}", err)),
+            sender,
+            receiver,
+        }
+    }
+
+    /// Returns a handle allowing to stop the server.
+    #[must_use]
+    pub fn get_handle(&self) -> WebhookServerHandle {
+        WebhookServerHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Sets a new address to bind the server to when it is used as an [`UpdateSource`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The address; default - `127.0.0.1:8080`.
+    pub fn with_address<T>(mut self, value: T) -> Self
+    where
+        T: Into<SocketAddr>,
+    {
+        self.address = value.into();
+        self
+    }
+
+    /// Sets a new limit for the size of a request body.
+    ///
+    /// Requests with a larger body are not processed
+    /// and reported to the update error handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Limit in bytes; default - 2 MiB.
+    pub fn with_body_limit(mut self, value: usize) -> Self {
+        self.body_limit = value;
+        self
+    }
+
+    /// Enables filtering of requests by the client IP address.
+    ///
+    /// Requests from addresses not allowed by the filter are rejected with `403 Forbidden`.
+    ///
+    /// Note that the filter requires [`axum::extract::ConnectInfo`],
+    /// so when you use the server as a [`Router`],
+    /// serve it using [`Router::into_make_service_with_connect_info`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The filter; use [`WebhookIpFilter::default`] to accept Telegram networks only.
+    pub fn with_ip_filter(mut self, value: WebhookIpFilter) -> Self {
+        self.ip_filter = Some(value);
+        self
+    }
+
+    /// Enables webhook registration when the server starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Webhook parameters.
+    pub fn with_registration(mut self, value: WebhookRegistration) -> Self {
+        self.registration = Some(value);
+        self
+    }
+
+    /// Sets a secret token expected in every incoming request.
+    ///
+    /// Requests without the `X-Telegram-Bot-Api-Secret-Token` header
+    /// or with a header value different from the given token are rejected with `401 Unauthorized`.
+    ///
+    /// Use the same value in [`crate::types::SetWebhook::with_secret_token`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The secret token; see [`generate_secret_token`].
+    pub fn with_secret_token<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.secret_token = Some(Arc::from(value.into()));
+        self
+    }
+
+    /// Enables HTTPS.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - TLS configuration; see [`SelfSignedCertificate`] for a self-signed certificate.
+    #[cfg(feature = "webhook-tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "webhook-tls")))]
+    pub fn with_tls(mut self, value: WebhookTlsConfig) -> Self {
+        self.tls_config = Some(value);
+        self
+    }
+
+    /// Sets a new handler for requests which do not contain a valid update.
+    ///
+    /// By default such requests are logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The handler to call.
+    pub fn with_update_error_handler<T>(mut self, value: T) -> Self
+    where
+        T: Fn(WebhookUpdateError) + Send + Sync + 'static,
+    {
+        self.update_error_handler = Arc::new(value);
+        self
+    }
+
+    /// Runs the server
+    ///
+    /// Returns the local address that the server was bound to
+    /// after the server is stopped using [`WebhookServerHandle::shutdown`].
+    ///
+    /// When a [`WebhookRegistration`] is set, the webhook is registered
+    /// after the server is bound to the address;
+    /// see [`WebhookRegistration::with_delete_on_shutdown`] to delete it
+    /// after the server is stopped or has failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to bind the server to.
+    pub async fn run<T>(self, address: T) -> Result<SocketAddr, WebhookServerError>
+    where
+        T: Into<SocketAddr>,
+    {
+        let listener = TcpListener::bind(address.into()).await?;
+        let local_address = listener.local_addr()?;
+        let router = self.create_router().into_make_service_with_connect_info::<SocketAddr>();
+        let secret_token = self.secret_token.as_deref();
+        let unregistration = match self.registration {
+            Some(registration) => Some(registration.register(secret_token).await?),
+            None => None,
+        };
+        let _sender = self.sender;
+        let mut receiver = self.receiver;
+        let shutdown_signal = async move {
+            receiver.recv().await;
+        };
+        #[cfg(feature = "webhook-tls")]
+        let result = match self.tls_config {
+            Some(tls_config) => tls::serve(listener, tls_config, router, shutdown_signal).await,
+            None => {
+                axum::serve(listener, router)
+                    .with_graceful_shutdown(shutdown_signal)
+                    .await
+            }
+        };
+        #[cfg(not(feature = "webhook-tls"))]
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(shutdown_signal)
+            .await;
+        // the webhook is unregistered even when the server has failed
+        let unregistered = match unregistration {
+            Some(unregistration) => unregistration.unregister().await,
+            None => Ok(()),
+        };
+        result?;
+        unregistered?;
+        Ok(local_address)
+    }
+
+    /// Starts the server and converts it into a stream of updates.
+    ///
+    /// The handler given to the constructor is not used, updates are yielded by the stream instead.
+    /// The stream ends when the server is stopped using [`WebhookServerHandle`];
+    /// the server is stopped when the stream is dropped.
+    ///
+    /// Errors occurred when running the server are logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to bind the server to.
+    pub fn into_stream<T>(mut self, address: T) -> impl Stream<Item = Update> + Send + 'static
+    where
+        T: Into<SocketAddr>,
+    {
+        let address = address.into();
+        let (update_sender, mut update_receiver) = channel(STREAM_CAPACITY);
+        let shutdown_sender = self.sender.clone();
+        let closed_sender = update_sender.clone();
+        let watcher = spawn(async move {
+            closed_sender.closed().await;
+            let _ = shutdown_sender.send(()).await;
+        });
+        self.route =
+            post(handle_update::<StreamHandler>).layer(Extension(Arc::new(StreamHandler { sender: update_sender })));
+        spawn(async move {
+            if let Err(err) = self.run(address).await {
+                error!("Webhook server error: {}", err);
+            }
+            watcher.abort();
+        });
+        stream! {
+            while let Some(update) = update_receiver.recv().await {
+                yield update
+            }
+        }
+    }
+
+    fn create_router(&self) -> Router {
+        let options = RequestOptions {
+            body_limit: self.body_limit,
+            update_error_handler: self.update_error_handler.clone(),
+        };
+        let mut router = Router::new()
+            .route(&self.path, self.route.clone())
+            .layer(Extension(Arc::new(options)));
+        if let Some(secret_token) = self.secret_token.clone() {
+            router = router.route_layer(from_fn_with_state(secret_token, verify_secret_token));
+        }
+        if let Some(ip_filter) = self.ip_filter.clone() {
+            router = router.route_layer(from_fn_with_state(ip_filter, verify_ip_address));
+        }
+        router
+    }
+}
+
+impl From<WebhookServer> for Router {
+    fn from(value: WebhookServer) -> Self {
+        value.create_router()
+    }
+}
+
+impl UpdateSource for WebhookServer {
+    fn into_stream(self) -> impl Stream<Item = Update> + Send + 'static {
+        let address = self.address;
+        WebhookServer::into_stream(self, address)
+    }
+}
+
+struct StreamHandler {
+    sender: Sender<Update>,
+}
+
+impl UpdateHandler for StreamHandler {
+    async fn handle(&self, update: Update) {
+        let _ = self.sender.send(update).await;
+    }
+}
+
+/// Allows to stop a webhook server.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+pub struct WebhookServerHandle {
+    sender: Sender<()>,
+}
+
+impl WebhookServerHandle {
+    /// Stops the associated server.
+    ///
+    /// The server stops accepting new connections and waits for active requests to complete.
+    pub async fn shutdown(self) {
+        let _ = self.sender.send(()).await;
+    }
+}
+
+/// Represents an update handler able to reply to a webhook request with a method call.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+pub trait WebhookUpdateHandler {
+    /// Handles a received update.
+    ///
+    /// Returns a method to be called in response to the update, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The received update from the Telegram Bot API.
+    fn handle(&self, update: Update) -> impl Future<Output = Option<WebhookReply>> + Send;
+}
+
+/// Represents a method call to be sent in the body of a webhook response.
+///
+/// Note that the result of such a call is not available;
+/// use [`Client::execute`] if you need to check it.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+#[derive(Debug)]
+pub struct WebhookReply {
+    payload: Payload,
+}
+
+impl<M> From<M> for WebhookReply
+where
+    M: Method,
+{
+    fn from(method: M) -> Self {
+        Self {
+            payload: method.into_payload(),
+        }
+    }
+}
+
+/// Represents an error occurred when reading an update from a webhook request.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+#[derive(Debug)]
+pub enum WebhookUpdateError {
+    /// Failed to read a request body, e.g. it exceeds the size limit.
+    Body(AxumError),
+    /// Failed to parse a request body as an update.
+    Parse {
+        /// The request body.
+        body: Bytes,
+        /// The request body parsed as JSON; `None` when the body is not a valid JSON.
+        value: Option<JsonValue>,
+        /// The error occurred when parsing the body as an update.
+        error: JsonError,
+    },
+}
+
+impl Error for WebhookUpdateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(match self {
+            Self::Body(err) => err,
+            Self::Parse { error, .. } => error,
+        })
+    }
+}
+
+impl fmt::Display for WebhookUpdateError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Body(err) => write!(out, "can not read request body: {}", err),
+            Self::Parse { error, .. } => write!(out, "can not parse update: {}", error),
+        }
+    }
+}
+
+/// Generates a random secret token suitable for [`crate::types::SetWebhook::with_secret_token`].
+///
+/// The token consists of 64 characters from the `A-Z`, `a-z`, `0-9`, `_` and `-` set.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+pub fn generate_secret_token() -> String {
+    let mut rng = rand::rng();
+    (0..SECRET_TOKEN_LENGTH)
+        .map(|_| {
+            let idx = rng.random_range(0..SECRET_TOKEN_ALPHABET.len());
+            char::from(SECRET_TOKEN_ALPHABET[idx])
+        })
+        .collect()
+}
+
+struct RequestOptions {
+    body_limit: usize,
+    update_error_handler: UpdateErrorHandler,
+}
+
+impl RequestOptions {
+    async fn read_update(&self, body: Body) -> Option<Update> {
+        let result = match to_bytes(body, self.body_limit).await {
+            Ok(body) => serde_json::from_slice(&body).map_err(|error| WebhookUpdateError::Parse {
+                value: serde_json::from_slice(&body).ok(),
+                body,
+                error,
+            }),
+            Err(err) => Err(WebhookUpdateError::Body(err)),
+        };
+        match result {
+            Ok(update) => Some(update),
+            Err(err) => {
+                (self.update_error_handler)(err);
+                None
+            }
+        }
+    }
+}
+
+async fn handle_update<H>(
+    Extension(handler): Extension<Arc<H>>,
+    Extension(options): Extension<Arc<RequestOptions>>,
+    body: Body,
+) -> StatusCode
+where
+    H: UpdateHandler,
+{
+    if let Some(update) = options.read_update(body).await {
+        handler.handle(update).await;
+    }
+    StatusCode::OK
+}
+
+async fn handle_update_with_reply<H>(
+    Extension(handler): Extension<Arc<H>>,
+    Extension(client): Extension<Client>,
+    Extension(options): Extension<Arc<RequestOptions>>,
+    body: Body,
+) -> Response
+where
+    H: WebhookUpdateHandler,
+{
+    let update = match options.read_update(body).await {
+        Some(update) => update,
+        None => return StatusCode::OK.into_response(),
+    };
+    let reply = match handler.handle(update).await {
+        Some(reply) => reply,
+        None => return StatusCode::OK.into_response(),
+    };
+    match reply.payload.into_json_method() {
+        Ok(body) => Json(body).into_response(),
+        Err(payload) => {
+            debug!("Reply can not be sent in a webhook response, executing it using the client");
+            spawn(async move {
+                if let Err(err) = client.execute(ReplyMethod(payload)).await {
+                    error!("An error has occurred while executing a webhook reply: {}", err);
+                }
+            });
+            StatusCode::OK.into_response()
+        }
+    }
+}
+
+struct ReplyMethod(Payload);
+
+impl Method for ReplyMethod {
+    type Response = JsonValue;
+
+    fn into_payload(self) -> Payload {
+        self.0
+    }
+}
+
+async fn verify_secret_token(State(secret_token): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let is_valid = request
+        .headers()
+        .get(SECRET_TOKEN_HEADER)
+        .is_some_and(|value| constant_time_eq(value.as_bytes(), secret_token.as_bytes()));
+    if is_valid {
+        next.run(request).await
+    } else {
+        warn!("Rejected a webhook request with missing or invalid secret token");
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    black_box(a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y))) == 0
+}
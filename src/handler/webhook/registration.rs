@@ -0,0 +1,258 @@
+use std::{collections::HashSet, error::Error, fmt, io::Error as IoError, sync::Arc};
+
+use log::{info, warn};
+
+use crate::{
+    api::{Client, ExecuteError},
+    types::{AllowedUpdate, DeleteWebhook, GetWebhookInfo, Integer, SetWebhook, WebhookInfo},
+};
+
+/// Represents a webhook to be registered when a [`super::WebhookServer`] starts.
+///
+/// The server calls [`SetWebhook`] after binding to an address,
+/// checks the result using [`GetWebhookInfo`]
+/// and optionally calls [`DeleteWebhook`] after a shutdown.
+///
+/// The secret token of the server is registered as well.
+///
+/// Use [`WebhookRegistration::with_info_handler`] to get the status of the webhook,
+/// including the most recent delivery error reported by Telegram.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+pub struct WebhookRegistration {
+    client: Client,
+    url: String,
+    allowed_updates: Option<HashSet<AllowedUpdate>>,
+    certificate: Option<String>,
+    delete_on_shutdown: bool,
+    drop_pending_updates: Option<bool>,
+    info_handler: Option<InfoHandler>,
+    ip_address: Option<String>,
+    max_connections: Option<Integer>,
+}
+
+type InfoHandler = Arc<dyn Fn(&WebhookInfo) + Send + Sync>;
+
+impl WebhookRegistration {
+    /// Creates a new `WebhookRegistration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Telegram Bot API Client.
+    /// * `url` - Public HTTPS URL of the server, including the path.
+    pub fn new<T>(client: Client, url: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            client,
+            url: url.into(),
+            allowed_updates: None,
+            certificate: None,
+            delete_on_shutdown: false,
+            drop_pending_updates: None,
+            info_handler: None,
+            ip_address: None,
+            max_connections: None,
+        }
+    }
+
+    /// Sets a new list of allowed update types.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - List of types you want your bot to receive.
+    pub fn with_allowed_updates(mut self, value: HashSet<AllowedUpdate>) -> Self {
+        self.allowed_updates = Some(value);
+        self
+    }
+
+    /// Sets a new certificate.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - PEM-encoded public key certificate; required when a self-signed certificate is used.
+    pub fn with_certificate<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.certificate = Some(value.into());
+        self
+    }
+
+    /// Sets a new value for the `delete_on_shutdown` flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Indicates whether to delete the webhook after the server is stopped; default - false.
+    pub fn with_delete_on_shutdown(mut self, value: bool) -> Self {
+        self.delete_on_shutdown = value;
+        self
+    }
+
+    /// Sets a new value for the `drop_pending_updates` flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Indicates whether to drop all pending updates.
+    pub fn with_drop_pending_updates(mut self, value: bool) -> Self {
+        self.drop_pending_updates = Some(value);
+        self
+    }
+
+    /// Sets a new handler for the webhook status.
+    ///
+    /// The handler is called with the result of [`GetWebhookInfo`] after the webhook is registered,
+    /// so an application can act on [`WebhookInfo::last_error_message`]
+    /// left by a previous deployment, e.g. report it to monitoring.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The handler.
+    pub fn with_info_handler<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&WebhookInfo) + Send + Sync + 'static,
+    {
+        self.info_handler = Some(Arc::new(value));
+        self
+    }
+
+    /// Sets a new IP address.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The fixed IP address which will be used to send webhook requests.
+    pub fn with_ip_address<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.ip_address = Some(value.into());
+        self
+    }
+
+    /// Sets a new number of max connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum allowed number of simultaneous HTTPS connections; 1-100; default - 40.
+    pub fn with_max_connections(mut self, value: Integer) -> Self {
+        self.max_connections = Some(value);
+        self
+    }
+
+    pub(super) async fn register(
+        self,
+        secret_token: Option<&str>,
+    ) -> Result<WebhookUnregistration, WebhookServerError> {
+        let mut method = SetWebhook::new(self.url.clone());
+        if let Some(value) = self.allowed_updates {
+            method = method.with_allowed_updates(value);
+        }
+        if let Some(value) = self.certificate {
+            method = method.with_certificate(value);
+        }
+        if let Some(value) = self.drop_pending_updates {
+            method = method.with_drop_pending_updates(value);
+        }
+        if let Some(value) = self.ip_address {
+            method = method.with_ip_address(value);
+        }
+        if let Some(value) = self.max_connections {
+            method = method.with_max_connections(value);
+        }
+        if let Some(value) = secret_token {
+            method = method.with_secret_token(value);
+        }
+        self.client.execute(method).await?;
+        let webhook_info = self.client.execute(GetWebhookInfo).await?;
+        if webhook_info.url != self.url {
+            return Err(WebhookServerError::UrlMismatch {
+                expected: self.url,
+                actual: webhook_info.url,
+            });
+        }
+        if let Some(ref message) = webhook_info.last_error_message {
+            warn!(
+                "The most recent error when delivering an update via webhook: {}",
+                message
+            );
+        }
+        if let Some(info_handler) = self.info_handler {
+            info_handler(&webhook_info);
+        }
+        info!("Webhook has been registered: {}", self.url);
+        Ok(WebhookUnregistration {
+            client: self.client,
+            delete_on_shutdown: self.delete_on_shutdown,
+        })
+    }
+}
+
+impl fmt::Debug for WebhookRegistration {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("WebhookRegistration")
+            .field("client", &self.client)
+            .field("url", &self.url)
+            .field("allowed_updates", &self.allowed_updates)
+            .field("certificate", &self.certificate)
+            .field("delete_on_shutdown", &self.delete_on_shutdown)
+            .field("drop_pending_updates", &self.drop_pending_updates)
+            .field("ip_address", &self.ip_address)
+            .field("max_connections", &self.max_connections)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(super) struct WebhookUnregistration {
+    client: Client,
+    delete_on_shutdown: bool,
+}
+
+impl WebhookUnregistration {
+    pub(super) async fn unregister(self) -> Result<(), WebhookServerError> {
+        if self.delete_on_shutdown {
+            self.client.execute(DeleteWebhook::default()).await?;
+            info!("Webhook has been deleted");
+        }
+        Ok(())
+    }
+}
+
+/// Represents an error occurred when running a webhook server.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
+#[derive(Debug, derive_more::From)]
+pub enum WebhookServerError {
+    /// Failed to execute a webhook management method.
+    Execute(ExecuteError),
+    /// An I/O error, e.g. failed to bind to an address.
+    Io(IoError),
+    /// The webhook URL returned by [`GetWebhookInfo`] differs from the registered one.
+    #[from(ignore)]
+    UrlMismatch {
+        /// The registered URL.
+        expected: String,
+        /// The URL returned by the Telegram Bot API.
+        actual: String,
+    },
+}
+
+impl Error for WebhookServerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Execute(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::UrlMismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for WebhookServerError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Execute(err) => write!(out, "can not manage webhook: {}", err),
+            Self::Io(err) => write!(out, "webhook server I/O error: {}", err),
+            Self::UrlMismatch { expected, actual } => {
+                write!(out, "webhook URL mismatch: expected={} actual={}", expected, actual)
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    io::{Cursor, Error as IoError, ErrorKind as IoErrorKind},
+    net::SocketAddr,
+    path::Path,
+    sync::Arc,
+};
+
+use axum::{extract::connect_info::IntoMakeServiceWithConnectInfo, Router};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use rcgen::{CertificateParams, DnType, Error as CertificateError, KeyPair};
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    ServerConfig,
+};
+use tokio::{fs, net::TcpListener, spawn};
+
+use crate::types::{InputFile, InputFileReader};
+
+/// Represents a TLS configuration for a webhook server.
+///
+/// Allows to serve HTTPS without a reverse proxy.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook-tls")))]
+#[derive(Clone)]
+pub struct WebhookTlsConfig {
+    certificate: Vec<u8>,
+    private_key: Vec<u8>,
+}
+
+impl WebhookTlsConfig {
+    /// Creates a new `WebhookTlsConfig` from PEM-encoded data.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - Certificate chain.
+    /// * `private_key` - Private key.
+    pub fn from_pem<A, B>(certificate: A, private_key: B) -> Self
+    where
+        A: Into<Vec<u8>>,
+        B: Into<Vec<u8>>,
+    {
+        Self {
+            certificate: certificate.into(),
+            private_key: private_key.into(),
+        }
+    }
+
+    /// Creates a new `WebhookTlsConfig` from PEM-encoded files.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - Path to a certificate chain.
+    /// * `private_key` - Path to a private key.
+    pub async fn from_pem_files<A, B>(certificate: A, private_key: B) -> Result<Self, IoError>
+    where
+        A: AsRef<Path>,
+        B: AsRef<Path>,
+    {
+        Ok(Self::from_pem(
+            fs::read(certificate).await?,
+            fs::read(private_key).await?,
+        ))
+    }
+
+    fn build_server_config(&self) -> Result<ServerConfig, IoError> {
+        let certificates = CertificateDer::pem_slice_iter(&self.certificate)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| IoError::new(IoErrorKind::InvalidData, err))?;
+        let private_key = PrivateKeyDer::from_pem_slice(&self.private_key)
+            .map_err(|err| IoError::new(IoErrorKind::InvalidData, err))?;
+        let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| {
+                builder
+                    .with_no_client_auth()
+                    .with_single_cert(certificates, private_key)
+            })
+            .map_err(|err| IoError::new(IoErrorKind::InvalidData, err))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+}
+
+impl fmt::Debug for WebhookTlsConfig {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("WebhookTlsConfig")
+            .field("certificate", &String::from_utf8_lossy(&self.certificate))
+            .field("private_key", &format_args!("..."))
+            .finish()
+    }
+}
+
+/// Represents a self-signed certificate for a webhook server.
+///
+/// Upload the certificate using [`crate::types::SetWebhook::with_certificate`],
+/// so that Telegram can verify the connection.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook-tls")))]
+#[derive(Clone)]
+pub struct SelfSignedCertificate {
+    certificate: String,
+    private_key: String,
+}
+
+impl SelfSignedCertificate {
+    /// Generates a new self-signed certificate.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - IP address or domain name of the webhook server.
+    pub fn generate<T>(host: T) -> Result<Self, SelfSignedCertificateError>
+    where
+        T: Into<String>,
+    {
+        let host = host.into();
+        let mut params = CertificateParams::new(vec![host.clone()])?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        let key_pair = KeyPair::generate()?;
+        let certificate = params.self_signed(&key_pair)?;
+        Ok(Self {
+            certificate: certificate.pem(),
+            private_key: key_pair.serialize_pem(),
+        })
+    }
+
+    /// Returns the PEM-encoded certificate.
+    pub fn certificate_pem(&self) -> &str {
+        &self.certificate
+    }
+
+    /// Returns the PEM-encoded private key.
+    pub fn private_key_pem(&self) -> &str {
+        &self.private_key
+    }
+
+    /// Returns the certificate as a file to upload.
+    pub fn to_input_file(&self) -> InputFile {
+        InputFileReader::new(Cursor::new(self.certificate.clone().into_bytes()))
+            .with_file_name("certificate.pem")
+            .into()
+    }
+
+    /// Returns a TLS configuration for a webhook server.
+    pub fn to_tls_config(&self) -> WebhookTlsConfig {
+        WebhookTlsConfig::from_pem(self.certificate.clone(), self.private_key.clone())
+    }
+}
+
+impl fmt::Debug for SelfSignedCertificate {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("SelfSignedCertificate")
+            .field("certificate", &self.certificate)
+            .field("private_key", &format_args!("..."))
+            .finish()
+    }
+}
+
+/// Represents an error occurred when generating a self-signed certificate.
+#[cfg_attr(nightly, doc(cfg(feature = "webhook-tls")))]
+#[derive(Debug, derive_more::From)]
+pub enum SelfSignedCertificateError {
+    /// Failed to generate a certificate or a private key.
+    Generate(CertificateError),
+}
+
+impl Error for SelfSignedCertificateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(match self {
+            Self::Generate(err) => err,
+        })
+    }
+}
+
+impl fmt::Display for SelfSignedCertificateError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Generate(err) => write!(out, "can not generate self-signed certificate: {}", err),
+        }
+    }
+}
+
+pub(super) async fn serve<S>(
+    listener: TcpListener,
+    config: WebhookTlsConfig,
+    router: IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+    shutdown_signal: S,
+) -> Result<(), IoError>
+where
+    S: Future<Output = ()> + Send + 'static,
+{
+    let config = RustlsConfig::from_config(Arc::new(config.build_server_config()?));
+    let handle = Handle::new();
+    let shutdown_handle = handle.clone();
+    spawn(async move {
+        shutdown_signal.await;
+        shutdown_handle.graceful_shutdown(None);
+    });
+    axum_server::from_tcp_rustls(listener.into_std()?, config)
+        .handle(handle)
+        .serve(router)
+        .await
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use toml::{Table, Value};
+
+use super::LocalizationError;
+
+const COMMANDS_KEY: &str = "commands";
+
+/// Represents a catalog of localized messages of a single language.
+///
+/// A catalog is usually loaded from a TOML file,
+/// where nested tables produce keys joined with `.`:
+///
+/// ```toml
+/// [bot]
+/// name = "Example bot"
+/// description = "Shows how to localize a bot"
+/// short_description = "Localized bot"
+///
+/// [commands]
+/// start = "Start the bot"
+/// help = "Show help"
+///
+/// [greeting]
+/// hello = "Hello, <b>{name}</b>!"
+/// ```
+///
+/// The `commands` table contains descriptions of bot commands in order of appearance,
+/// and the `bot` table contains the name and descriptions of the bot;
+/// see [`super::Localization::sync_bot_info`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    commands: Vec<(String, String)>,
+}
+
+impl Catalog {
+    /// Parses a catalog from a TOML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The TOML document; all values must be strings.
+    pub fn from_toml(value: &str) -> Result<Self, LocalizationError> {
+        let mut table: Table = value.parse()?;
+        let mut result = Self::default();
+        if let Some(commands) = table.remove(COMMANDS_KEY) {
+            let commands = match commands {
+                Value::Table(commands) => commands,
+                _ => return Err(LocalizationError::InvalidValue(String::from(COMMANDS_KEY))),
+            };
+            for (name, description) in commands {
+                match description {
+                    Value::String(description) => result.commands.push((name, description)),
+                    _ => return Err(LocalizationError::InvalidValue(format!("{}.{}", COMMANDS_KEY, name))),
+                }
+            }
+        }
+        result.insert_table(String::new(), table)?;
+        Ok(result)
+    }
+
+    fn insert_table(&mut self, prefix: String, table: Table) -> Result<(), LocalizationError> {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::String(value) => {
+                    self.messages.insert(key, value);
+                }
+                Value::Table(table) => self.insert_table(key, table)?,
+                _ => return Err(LocalizationError::InvalidValue(key)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a message to the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the message.
+    /// * `value` - The message.
+    pub fn with_message<A, B>(mut self, key: A, value: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        self.messages.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds a description of a bot command to the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the command without the leading slash.
+    /// * `description` - The description of the command.
+    pub fn with_command<A, B>(mut self, name: A, description: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        self.commands.push((name.into(), description.into()));
+        self
+    }
+
+    /// Returns a message by key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the message.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    /// Returns names and descriptions of bot commands in order of appearance.
+    pub fn get_commands(&self) -> &[(String, String)] {
+        &self.commands
+    }
+}
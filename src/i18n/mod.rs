@@ -0,0 +1,358 @@
+use std::{collections::HashMap, error::Error, fmt, io::Error as IoError, path::Path};
+
+use tokio::fs;
+use toml::de::Error as TomlError;
+
+pub use self::catalog::*;
+use crate::{
+    api::{Client, ExecuteError},
+    types::{
+        BotCommandError,
+        CommandDescriptor,
+        CommandRegistry,
+        SetBotDescription,
+        SetBotName,
+        SetBotShortDescription,
+        Text,
+        TextTemplate,
+        TextTemplateError,
+        TextTemplateValue,
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+mod catalog;
+
+const BOT_NAME_KEY: &str = "bot.name";
+const BOT_DESCRIPTION_KEY: &str = "bot.description";
+const BOT_SHORT_DESCRIPTION_KEY: &str = "bot.short_description";
+
+/// Represents a set of message catalogs keyed by a language.
+///
+/// Languages are normalized to lowercase with `-` as a separator,
+/// so `pt_BR` and `pt-br` refer to the same catalog.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::i18n::{Catalog, Localization};
+///
+/// let localization = Localization::new("en")
+///     .with_catalog("en", Catalog::default().with_message("hello", "Hello, <b>{name}</b>!"))
+///     .with_catalog("de", Catalog::default().with_message("hello", "Hallo, <b>{name}</b>!"));
+/// let text = localization
+///     .get_locale(Some("de-AT"))
+///     .render("hello", [("name", "<Max>")])
+///     .unwrap();
+/// assert_eq!(text.data, "Hallo, <Max>!");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Localization {
+    catalogs: HashMap<String, Catalog>,
+    default_language: String,
+}
+
+impl Localization {
+    /// Creates a new `Localization` without catalogs.
+    ///
+    /// # Arguments
+    ///
+    /// * `default_language` - The language used when no catalog matches a language code.
+    pub fn new<T>(default_language: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self {
+            catalogs: HashMap::new(),
+            default_language: normalize_language(default_language.as_ref()),
+        }
+    }
+
+    /// Loads catalogs from a directory.
+    ///
+    /// Each file with the `.toml` extension is parsed using [`Catalog::from_toml`],
+    /// and the name of the file without the extension is used as a language.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the directory.
+    /// * `default_language` - The language used when no catalog matches a language code.
+    pub async fn load_dir<P, T>(path: P, default_language: T) -> Result<Self, LocalizationError>
+    where
+        P: AsRef<Path>,
+        T: AsRef<str>,
+    {
+        let mut result = Self::new(default_language);
+        let mut entries = fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|x| x != "toml") {
+                continue;
+            }
+            let language = match path.file_stem().and_then(|x| x.to_str()) {
+                Some(language) => String::from(language),
+                None => continue,
+            };
+            let data = fs::read_to_string(&path).await?;
+            result = result.with_catalog(language, Catalog::from_toml(&data)?);
+        }
+        Ok(result)
+    }
+
+    /// Adds a catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `language` - The language of the catalog.
+    /// * `catalog` - The catalog.
+    pub fn with_catalog<T>(mut self, language: T, catalog: Catalog) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.catalogs.insert(normalize_language(language.as_ref()), catalog);
+        self
+    }
+
+    /// Returns the default language.
+    pub fn get_default_language(&self) -> &str {
+        &self.default_language
+    }
+
+    /// Returns a sorted list of languages having a catalog.
+    pub fn get_languages(&self) -> Vec<&str> {
+        let mut result: Vec<&str> = self.catalogs.keys().map(String::as_str).collect();
+        result.sort_unstable();
+        result
+    }
+
+    /// Returns a locale for a language code.
+    ///
+    /// Messages are looked up using a fallback chain:
+    /// the language code itself, its primary subtag (`pt` for `pt-BR`)
+    /// and finally the default language.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_code` - The language code, usually [`crate::types::User::language_code`].
+    pub fn get_locale(&self, language_code: Option<&str>) -> Locale<'_> {
+        let mut languages = Vec::new();
+        if let Some(language_code) = language_code {
+            let language = normalize_language(language_code);
+            if let Some((primary, _)) = language.split_once('-') {
+                let primary = String::from(primary);
+                languages.push(language);
+                languages.push(primary);
+            } else {
+                languages.push(language);
+            }
+        }
+        languages.push(self.default_language.clone());
+        let mut catalogs: Vec<(&str, &Catalog)> = Vec::new();
+        for language in languages {
+            if let Some((language, catalog)) = self.catalogs.get_key_value(&language) {
+                if catalogs.iter().all(|(x, _)| *x != language) {
+                    catalogs.push((language, catalog));
+                }
+            }
+        }
+        Locale { catalogs }
+    }
+
+    /// Returns a registry of bot commands.
+    ///
+    /// Commands are taken from the catalog of the default language,
+    /// and their descriptions are translated using catalogs of other languages.
+    /// Telegram accepts only two-letter language codes,
+    /// so catalogs of other languages, such as `pt-br`, are skipped.
+    pub fn get_command_registry(&self) -> CommandRegistry {
+        let mut result = CommandRegistry::default();
+        let commands = match self.catalogs.get(&self.default_language) {
+            Some(catalog) => catalog.get_commands(),
+            None => return result,
+        };
+        for (name, description) in commands {
+            let mut descriptor = CommandDescriptor::new(name.as_str(), description.as_str());
+            for language in self.get_languages() {
+                if !is_language_code(language) {
+                    continue;
+                }
+                let translation = self.catalogs[language].get_commands().iter().find(|(x, _)| x == name);
+                if let Some((_, description)) = translation {
+                    descriptor = descriptor.with_translation(language, description.as_str());
+                }
+            }
+            result = result.with_command(descriptor);
+        }
+        result
+    }
+
+    /// Sets the name, descriptions and commands of the bot for each language.
+    ///
+    /// Values are taken from `bot.name`, `bot.description` and `bot.short_description` messages
+    /// of a catalog; missing values are not changed.
+    /// Commands are set using [`CommandRegistry::get_set_bot_commands`],
+    /// see [`Localization::get_command_registry`].
+    ///
+    /// Values of the default language are also set without a language code,
+    /// so they are shown to users whose language has no dedicated values.
+    /// Telegram accepts only two-letter language codes,
+    /// so catalogs of other languages, such as `pt-br`, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client used to execute methods.
+    pub async fn sync_bot_info(&self, client: &Client) -> Result<(), LocalizationError> {
+        if let Some(catalog) = self.catalogs.get(&self.default_language) {
+            sync_catalog(client, catalog, None).await?;
+        }
+        for language in self.get_languages() {
+            if is_language_code(language) {
+                sync_catalog(client, &self.catalogs[language], Some(language)).await?;
+            }
+        }
+        for method in self.get_command_registry().get_set_bot_commands()? {
+            client.execute(method).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn sync_catalog(client: &Client, catalog: &Catalog, language: Option<&str>) -> Result<(), LocalizationError> {
+    if let Some(value) = catalog.get(BOT_NAME_KEY) {
+        let mut method = SetBotName::default().with_name(value);
+        if let Some(language) = language {
+            method = method.with_language_code(language);
+        }
+        client.execute(method).await?;
+    }
+    if let Some(value) = catalog.get(BOT_DESCRIPTION_KEY) {
+        let mut method = SetBotDescription::default().with_description(value);
+        if let Some(language) = language {
+            method = method.with_language_code(language);
+        }
+        client.execute(method).await?;
+    }
+    if let Some(value) = catalog.get(BOT_SHORT_DESCRIPTION_KEY) {
+        let mut method = SetBotShortDescription::default().with_short_description(value);
+        if let Some(language) = language {
+            method = method.with_language_code(language);
+        }
+        client.execute(method).await?;
+    }
+    Ok(())
+}
+
+fn is_language_code(value: &str) -> bool {
+    value.len() == 2 && value.chars().all(|x| x.is_ascii_lowercase())
+}
+
+fn normalize_language(value: &str) -> String {
+    value.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+/// Represents a chain of catalogs selected for a language code.
+///
+/// Use [`Localization::get_locale`] to get a locale.
+#[derive(Clone, Debug)]
+pub struct Locale<'a> {
+    catalogs: Vec<(&'a str, &'a Catalog)>,
+}
+
+impl<'a> Locale<'a> {
+    /// Returns the language of the first catalog in the chain.
+    pub fn get_language(&self) -> Option<&'a str> {
+        self.catalogs.first().map(|(language, _)| *language)
+    }
+
+    /// Returns a message by key from the first catalog containing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the message.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.catalogs.iter().find_map(|(_, catalog)| catalog.get(key))
+    }
+
+    /// Returns a message by key as a template.
+    ///
+    /// The message is parsed using [`TextTemplate::from_html`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the message.
+    pub fn get_template(&self, key: &str) -> Result<TextTemplate, LocalizationError> {
+        let value = self
+            .get(key)
+            .ok_or_else(|| LocalizationError::MissingMessage(String::from(key)))?;
+        Ok(TextTemplate::from_html(value)?)
+    }
+
+    /// Renders a message by key.
+    ///
+    /// See [`TextTemplate::render`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the message.
+    /// * `values` - Values of placeholders.
+    pub fn render<I, K, V>(&self, key: &str, values: I) -> Result<Text, LocalizationError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<TextTemplateValue>,
+    {
+        Ok(self.get_template(key)?.render(values)?)
+    }
+}
+
+/// Represents an error occurred when using [`Localization`].
+#[derive(Debug, derive_more::From)]
+pub enum LocalizationError {
+    /// A command in a catalog is invalid.
+    Command(BotCommandError),
+    /// Failed to execute a method.
+    Execute(ExecuteError),
+    /// A value in a catalog is not a string.
+    #[from(ignore)]
+    InvalidValue(String),
+    /// Failed to read catalogs.
+    Io(IoError),
+    /// A message is not found in any catalog of a locale.
+    #[from(ignore)]
+    MissingMessage(String),
+    /// A message is not a valid template.
+    Template(TextTemplateError),
+    /// Failed to parse a catalog.
+    Toml(TomlError),
+}
+
+impl Error for LocalizationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::LocalizationError::*;
+        match self {
+            Command(err) => Some(err),
+            Execute(err) => Some(err),
+            InvalidValue(_) | MissingMessage(_) => None,
+            Io(err) => Some(err),
+            Template(err) => Some(err),
+            Toml(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for LocalizationError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::LocalizationError::*;
+        match self {
+            Command(err) => write!(out, "invalid command: {}", err),
+            Execute(err) => write!(out, "{}", err),
+            InvalidValue(key) => write!(out, "value of \"{}\" must be a string", key),
+            Io(err) => write!(out, "can not read catalog: {}", err),
+            MissingMessage(key) => write!(out, "message \"{}\" not found", key),
+            Template(err) => write!(out, "invalid template: {}", err),
+            Toml(err) => write!(out, "can not parse catalog: {}", err),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    i18n::{Catalog, Localization, LocalizationError},
+    types::{TextEntity, TextTemplateValue, User},
+};
+
+#[test]
+fn catalog_from_toml() {
+    let catalog = Catalog::from_toml(
+        r#"
+        title = "Title"
+
+        [commands]
+        start = "Start"
+        help = "Help"
+        about = "About"
+
+        [a.b]
+        c = "d"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        catalog,
+        Catalog::default()
+            .with_message("title", "Title")
+            .with_message("a.b.c", "d")
+            .with_command("start", "Start")
+            .with_command("help", "Help")
+            .with_command("about", "About")
+    );
+    assert_eq!(catalog.get("a.b.c"), Some("d"));
+    assert_eq!(catalog.get("a.b"), None);
+
+    for (input, key) in [
+        ("a = 1", "a"),
+        ("[a]\nb = [\"c\"]", "a.b"),
+        ("commands = \"a\"", "commands"),
+    ] {
+        match Catalog::from_toml(input) {
+            Err(LocalizationError::InvalidValue(value)) => assert_eq!(value, key),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+    assert!(matches!(Catalog::from_toml("a ="), Err(LocalizationError::Toml(_))));
+}
+
+#[test]
+fn locale() {
+    let localization = Localization::new("EN")
+        .with_catalog(
+            "en",
+            Catalog::default().with_message("a", "en-a").with_message("b", "en-b"),
+        )
+        .with_catalog("pt", Catalog::default().with_message("a", "pt-a"))
+        .with_catalog("pt_BR", Catalog::default().with_message("c", "pt-br-c"));
+    assert_eq!(localization.get_default_language(), "en");
+    assert_eq!(localization.get_languages(), vec!["en", "pt", "pt-br"]);
+
+    let locale = localization.get_locale(Some("pt-BR"));
+    assert_eq!(locale.get_language(), Some("pt-br"));
+    assert_eq!(locale.get("a"), Some("pt-a"));
+    assert_eq!(locale.get("b"), Some("en-b"));
+    assert_eq!(locale.get("c"), Some("pt-br-c"));
+    assert_eq!(locale.get("d"), None);
+
+    let locale = localization.get_locale(Some("pt-PT"));
+    assert_eq!(locale.get_language(), Some("pt"));
+    assert_eq!(locale.get("c"), None);
+
+    for language_code in [None, Some("de"), Some("en-US")] {
+        let locale = localization.get_locale(language_code);
+        assert_eq!(locale.get_language(), Some("en"));
+        assert_eq!(locale.get("a"), Some("en-a"));
+    }
+
+    let localization = Localization::new("en");
+    let locale = localization.get_locale(Some("en"));
+    assert_eq!(locale.get_language(), None);
+    assert_eq!(locale.get("a"), None);
+}
+
+#[test]
+fn command_registry() {
+    let localization = Localization::new("en")
+        .with_catalog(
+            "en",
+            Catalog::default()
+                .with_command("start", "Start")
+                .with_command("help", "Help"),
+        )
+        .with_catalog("de", Catalog::default().with_command("start", "Starten"))
+        .with_catalog("pt_BR", Catalog::default().with_command("start", "Iniciar"));
+    let registry = localization.get_command_registry();
+    assert_eq!(registry.get_help_text(None), "/start - Start\n/help - Help");
+    assert_eq!(registry.get_help_text(Some("de")), "/start - Starten\n/help - Help");
+    assert_eq!(registry.get_help_text(Some("pt-br")), "/start - Start\n/help - Help");
+    assert_eq!(registry.get_set_bot_commands().unwrap().len(), 3);
+
+    let localization = Localization::new("en").with_catalog("de", Catalog::default().with_command("start", "Starten"));
+    assert!(localization
+        .get_command_registry()
+        .get_set_bot_commands()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn render() {
+    let localization = Localization::new("en").with_catalog(
+        "en",
+        Catalog::default()
+            .with_message("hello", "Hello, <b>{user}</b>!")
+            .with_message("invalid", "Hello, {user!"),
+    );
+    let locale = localization.get_locale(Some("en"));
+    let user = User::new(1, "John", false);
+    let text = locale
+        .render("hello", [("user", TextTemplateValue::from(user.clone()))])
+        .unwrap();
+    assert_eq!(text.data, "Hello, John!");
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![TextEntity::bold(7..11), TextEntity::text_mention(7..11, user)]
+    );
+    assert!(matches!(
+        locale.render("missing", [("user", "John")]),
+        Err(LocalizationError::MissingMessage(key)) if key == "missing"
+    ));
+    assert!(matches!(
+        locale.get_template("invalid"),
+        Err(LocalizationError::Template(_))
+    ));
+    assert!(matches!(
+        locale.render("hello", [("name", "John")]),
+        Err(LocalizationError::Template(_))
+    ));
+}
@@ -7,5 +7,10 @@ pub mod api;
 /// Update handlers
 pub mod handler;
 
+/// Localization
+#[cfg(feature = "i18n")]
+#[cfg_attr(nightly, doc(cfg(feature = "i18n")))]
+pub mod i18n;
+
 /// Telegram Bot API types
 pub mod types;
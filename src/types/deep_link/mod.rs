@@ -0,0 +1,398 @@
+use std::{error::Error, fmt};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, DecodeError, Engine};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Error as JsonError;
+
+use crate::types::{ChatAdministratorRights, Command};
+
+#[cfg(test)]
+mod tests;
+
+const BASE_URL: &str = "https://t.me";
+const START_COMMAND: &str = "/start";
+
+/// Represents a payload of a deep link.
+///
+/// A payload contains characters `A-Z`, `a-z`, `0-9`, `_` and `-`,
+/// up to 512 in a `startapp` link and up to 64 in other links.
+/// Arbitrary data can be encoded using base64url.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DeepLinkPayload(String);
+
+impl DeepLinkPayload {
+    const MAX_LEN: usize = 512;
+    const MAX_START_LEN: usize = 64;
+
+    /// Creates a new `DeepLinkPayload`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The payload as it appears in a link.
+    pub fn new<T>(value: T) -> Result<Self, DeepLinkError>
+    where
+        T: Into<String>,
+    {
+        let value = value.into();
+        let len = value.len();
+        if len == 0 || len > Self::MAX_LEN {
+            return Err(DeepLinkError::BadPayloadLen(len));
+        }
+        if let Some(c) = value
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+        {
+            return Err(DeepLinkError::BadPayloadChar(c));
+        }
+        Ok(Self(value))
+    }
+
+    /// Creates a new `DeepLinkPayload` from bytes encoded using base64url without padding.
+    ///
+    /// Up to 384 bytes can be encoded, and up to 48 bytes fit into links other than `startapp`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Bytes to encode.
+    pub fn encode<T>(value: T) -> Result<Self, DeepLinkError>
+    where
+        T: AsRef<[u8]>,
+    {
+        Self::new(URL_SAFE_NO_PAD.encode(value))
+    }
+
+    /// Creates a new `DeepLinkPayload` from a value serialized as JSON and encoded using base64url.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to encode.
+    pub fn encode_json<T>(value: &T) -> Result<Self, DeepLinkError>
+    where
+        T: Serialize,
+    {
+        Self::encode(serde_json::to_vec(value)?)
+    }
+
+    /// Decodes bytes created using [`DeepLinkPayload::encode`].
+    pub fn decode(&self) -> Result<Vec<u8>, DeepLinkError> {
+        Ok(URL_SAFE_NO_PAD.decode(&self.0)?)
+    }
+
+    /// Decodes a value created using [`DeepLinkPayload::encode_json`].
+    pub fn decode_json<T>(&self) -> Result<T, DeepLinkError>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(serde_json::from_slice(&self.decode()?)?)
+    }
+
+    /// Returns the payload as it appears in a link.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn check_len(self, max_len: usize) -> Result<Self, DeepLinkError> {
+        let len = self.0.len();
+        if len > max_len {
+            Err(DeepLinkError::PayloadTooLong { len, max_len })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl fmt::Display for DeepLinkPayload {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.write_str(&self.0)
+    }
+}
+
+impl TryFrom<&Command> for DeepLinkPayload {
+    type Error = DeepLinkError;
+
+    /// Reads a payload from a `/start <payload>` command.
+    fn try_from(value: &Command) -> Result<Self, Self::Error> {
+        if value.get_name() != START_COMMAND {
+            return Err(DeepLinkError::NotStartCommand);
+        }
+        Self::new(value.get_raw_args())?.check_len(Self::MAX_START_LEN)
+    }
+}
+
+/// Represents a deep link to a bot.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::types::{ChatAdministratorRights, DeepLink, DeepLinkPayload};
+///
+/// let payload = DeepLinkPayload::encode_json(&("ref", 42)).unwrap();
+/// let link = DeepLink::start("my_bot", payload).unwrap();
+/// assert_eq!(link.to_string(), "https://t.me/my_bot?start=WyJyZWYiLDQyXQ");
+///
+/// let rights = ChatAdministratorRights::default().with_can_invite_users(true);
+/// let link = DeepLink::start_group("my_bot").with_admin_rights(rights);
+/// assert_eq!(link.to_string(), "https://t.me/my_bot?startgroup&admin=invite_users");
+/// ```
+#[derive(Clone, Debug)]
+pub struct DeepLink {
+    bot_username: String,
+    kind: DeepLinkKind,
+    admin_rights: Option<ChatAdministratorRights>,
+    app_name: Option<String>,
+    payload: Option<DeepLinkPayload>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DeepLinkKind {
+    Start,
+    StartApp,
+    StartAttach,
+    StartChannel,
+    StartGroup,
+}
+
+impl DeepLinkKind {
+    fn get_max_payload_len(self) -> usize {
+        match self {
+            Self::StartApp => DeepLinkPayload::MAX_LEN,
+            _ => DeepLinkPayload::MAX_START_LEN,
+        }
+    }
+}
+
+impl DeepLink {
+    fn new<T>(bot_username: T, kind: DeepLinkKind) -> Self
+    where
+        T: Into<String>,
+    {
+        let bot_username = bot_username.into();
+        Self {
+            bot_username: String::from(bot_username.trim_start_matches('@')),
+            kind,
+            admin_rights: None,
+            app_name: None,
+            payload: None,
+        }
+    }
+
+    /// Creates a link opening a private chat with a bot (`?start=`).
+    ///
+    /// The bot receives the `/start <payload>` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_username` - The username of the bot, see [`crate::types::Bot::username`].
+    /// * `payload` - The payload; up to 64 characters.
+    pub fn start<T>(bot_username: T, payload: DeepLinkPayload) -> Result<Self, DeepLinkError>
+    where
+        T: Into<String>,
+    {
+        Self::new(bot_username, DeepLinkKind::Start).with_payload(payload)
+    }
+
+    /// Creates a link adding a bot to a group (`?startgroup`).
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_username` - The username of the bot.
+    pub fn start_group<T>(bot_username: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(bot_username, DeepLinkKind::StartGroup)
+    }
+
+    /// Creates a link adding a bot to a channel (`?startchannel`).
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_username` - The username of the bot.
+    /// * `admin_rights` - Administrator rights requested by the bot.
+    pub fn start_channel<T>(bot_username: T, admin_rights: ChatAdministratorRights) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(bot_username, DeepLinkKind::StartChannel).with_admin_rights(admin_rights)
+    }
+
+    /// Creates a link opening a Mini App of a bot (`?startapp`).
+    ///
+    /// The payload is passed in the `start_param` of the Mini App init data.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_username` - The username of the bot.
+    pub fn start_app<T>(bot_username: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(bot_username, DeepLinkKind::StartApp)
+    }
+
+    /// Creates a link opening an attachment menu of a bot (`?startattach`).
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_username` - The username of the bot.
+    pub fn start_attach<T>(bot_username: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(bot_username, DeepLinkKind::StartAttach)
+    }
+
+    /// Sets a new payload.
+    ///
+    /// Not used in a `startchannel` link.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The payload; up to 512 characters in a `startapp` link and up to 64 in other links.
+    pub fn with_payload(mut self, value: DeepLinkPayload) -> Result<Self, DeepLinkError> {
+        self.payload = Some(value.check_len(self.kind.get_max_payload_len())?);
+        Ok(self)
+    }
+
+    /// Sets new administrator rights requested by a bot.
+    ///
+    /// Used in `startgroup` and `startchannel` links only.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Administrator rights.
+    pub fn with_admin_rights(mut self, value: ChatAdministratorRights) -> Self {
+        self.admin_rights = Some(value);
+        self
+    }
+
+    /// Sets a new name of a Mini App.
+    ///
+    /// Used in a `startapp` link only; the main Mini App is opened by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The short name of the Mini App.
+    pub fn with_app_name<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.app_name = Some(value.into());
+        self
+    }
+}
+
+impl fmt::Display for DeepLink {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "{}/{}", BASE_URL, self.bot_username)?;
+        let (name, payload, admin_rights) = match self.kind {
+            DeepLinkKind::Start => ("start", self.payload.as_ref(), None),
+            DeepLinkKind::StartApp => {
+                if let Some(ref app_name) = self.app_name {
+                    write!(out, "/{}", app_name)?;
+                }
+                ("startapp", self.payload.as_ref(), None)
+            }
+            DeepLinkKind::StartAttach => ("startattach", self.payload.as_ref(), None),
+            DeepLinkKind::StartChannel => ("startchannel", None, self.admin_rights.as_ref()),
+            DeepLinkKind::StartGroup => ("startgroup", self.payload.as_ref(), self.admin_rights.as_ref()),
+        };
+        write!(out, "?{}", name)?;
+        if let Some(payload) = payload {
+            write!(out, "={}", payload)?;
+        }
+        if let Some(admin_rights) = admin_rights {
+            let admin_rights = get_admin_rights_param(admin_rights);
+            if !admin_rights.is_empty() {
+                write!(out, "&admin={}", admin_rights.join("+"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn get_admin_rights_param(value: &ChatAdministratorRights) -> Vec<&'static str> {
+    [
+        (value.can_change_info, "change_info"),
+        (value.can_post_messages.unwrap_or(false), "post_messages"),
+        (value.can_edit_messages.unwrap_or(false), "edit_messages"),
+        (value.can_delete_messages, "delete_messages"),
+        (value.can_restrict_members, "restrict_members"),
+        (value.can_invite_users, "invite_users"),
+        (value.can_pin_messages.unwrap_or(false), "pin_messages"),
+        (value.can_manage_topics.unwrap_or(false), "manage_topics"),
+        (value.can_promote_members, "promote_members"),
+        (value.can_manage_video_chats, "manage_video_chats"),
+        (value.is_anonymous, "anonymous"),
+        (value.can_manage_chat, "manage_chat"),
+        (value.can_post_stories.unwrap_or(false), "post_stories"),
+        (value.can_edit_stories.unwrap_or(false), "edit_stories"),
+        (value.can_delete_stories.unwrap_or(false), "delete_stories"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, name)| enabled.then_some(name))
+    .collect()
+}
+
+/// Represents an error occurred when building or parsing a deep link.
+#[derive(Debug, derive_more::From)]
+pub enum DeepLinkError {
+    /// A payload contains a character which is not allowed.
+    #[from(ignore)]
+    BadPayloadChar(char),
+    /// A payload has an invalid length.
+    #[from(ignore)]
+    BadPayloadLen(usize),
+    /// Failed to decode a payload.
+    Decode(DecodeError),
+    /// Failed to serialize or deserialize a payload.
+    Json(JsonError),
+    /// A command is not `/start`.
+    NotStartCommand,
+    /// A payload is too long for a kind of a link.
+    #[from(ignore)]
+    PayloadTooLong {
+        /// The length of the payload.
+        len: usize,
+        /// The maximum length for the link.
+        max_len: usize,
+    },
+}
+
+impl Error for DeepLinkError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::DeepLinkError::*;
+        match self {
+            Decode(err) => Some(err),
+            Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeepLinkError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::DeepLinkError::*;
+        match self {
+            BadPayloadChar(c) => write!(
+                out,
+                "deep link payload contains a character which is not allowed: {}",
+                c
+            ),
+            BadPayloadLen(len) => write!(
+                out,
+                "deep link payload can have a length of 1 up to {} characters, got {}",
+                DeepLinkPayload::MAX_LEN,
+                len
+            ),
+            Decode(err) => write!(out, "can not decode deep link payload: {}", err),
+            Json(err) => write!(out, "can not serialize deep link payload: {}", err),
+            NotStartCommand => write!(out, "deep link payload can be read from the /start command only"),
+            PayloadTooLong { len, max_len } => write!(
+                out,
+                "deep link payload can have up to {} characters in this link, got {}",
+                max_len, len
+            ),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ChatAdministratorRights, Command, DeepLink, DeepLinkError, DeepLinkPayload, Message};
+
+fn create_command(text: &str) -> Command {
+    let len = text.split_whitespace().next().unwrap().len();
+    let message: Message = serde_json::from_value(serde_json::json!({
+        "message_id": 1,
+        "date": 0,
+        "from": {"id": 1, "is_bot": false, "first_name": "test"},
+        "chat": {"id": 1, "type": "private", "first_name": "test"},
+        "text": text,
+        "entities": [
+            {"type": "bot_command", "offset": 0, "length": len}
+        ]
+    }))
+    .unwrap();
+    Command::try_from(message).unwrap()
+}
+
+#[test]
+fn deep_link_payload() {
+    let payload = DeepLinkPayload::new("abc_DEF-123").unwrap();
+    assert_eq!(payload.as_str(), "abc_DEF-123");
+    assert_eq!(payload.to_string(), "abc_DEF-123");
+
+    assert!(matches!(
+        DeepLinkPayload::new("").unwrap_err(),
+        DeepLinkError::BadPayloadLen(0)
+    ));
+    assert!(matches!(
+        DeepLinkPayload::new("a".repeat(513)).unwrap_err(),
+        DeepLinkError::BadPayloadLen(513)
+    ));
+    assert!(DeepLinkPayload::new("a".repeat(512)).is_ok());
+    assert!(matches!(
+        DeepLinkPayload::new("a b").unwrap_err(),
+        DeepLinkError::BadPayloadChar(' ')
+    ));
+    assert!(matches!(
+        DeepLinkPayload::new("ёж").unwrap_err(),
+        DeepLinkError::BadPayloadChar('ё')
+    ));
+}
+
+#[test]
+fn deep_link_payload_encode() {
+    let payload = DeepLinkPayload::encode([0xfb, 0xff, 0x00]).unwrap();
+    assert_eq!(payload.as_str(), "-_8A");
+    assert_eq!(payload.decode().unwrap(), vec![0xfb, 0xff, 0x00]);
+
+    assert!(DeepLinkPayload::encode([0; 384]).is_ok());
+    assert!(matches!(
+        DeepLinkPayload::encode([0; 385]).unwrap_err(),
+        DeepLinkError::BadPayloadLen(514)
+    ));
+
+    let payload = DeepLinkPayload::new("a").unwrap();
+    assert!(matches!(payload.decode().unwrap_err(), DeepLinkError::Decode(_)));
+}
+
+#[test]
+fn deep_link_payload_encode_json() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Referral {
+        source: String,
+        id: u64,
+    }
+
+    let value = Referral {
+        source: String::from("ad"),
+        id: 42,
+    };
+    let payload = DeepLinkPayload::encode_json(&value).unwrap();
+    assert_eq!(payload.decode_json::<Referral>().unwrap(), value);
+
+    let payload = DeepLinkPayload::encode("not json").unwrap();
+    assert!(matches!(
+        payload.decode_json::<Referral>().unwrap_err(),
+        DeepLinkError::Json(_)
+    ));
+}
+
+#[test]
+fn deep_link_payload_from_command() {
+    let command = create_command("/start abc-123");
+    let payload = DeepLinkPayload::try_from(&command).unwrap();
+    assert_eq!(payload.as_str(), "abc-123");
+
+    let command = create_command("/start");
+    assert!(matches!(
+        DeepLinkPayload::try_from(&command).unwrap_err(),
+        DeepLinkError::BadPayloadLen(0)
+    ));
+
+    let command = create_command(&format!("/start {}", "a".repeat(65)));
+    assert!(matches!(
+        DeepLinkPayload::try_from(&command).unwrap_err(),
+        DeepLinkError::PayloadTooLong { len: 65, max_len: 64 }
+    ));
+
+    let command = create_command("/help abc");
+    assert!(matches!(
+        DeepLinkPayload::try_from(&command).unwrap_err(),
+        DeepLinkError::NotStartCommand
+    ));
+}
+
+#[test]
+fn deep_link() {
+    let payload = DeepLinkPayload::new("abc").unwrap();
+    let rights = ChatAdministratorRights::default()
+        .with_can_change_info(true)
+        .with_can_delete_messages(true)
+        .with_can_post_messages(true)
+        .with_can_manage_topics(false);
+
+    for (expected, link) in [
+        (
+            "https://t.me/my_bot?start=abc",
+            DeepLink::start("@my_bot", payload.clone()).unwrap(),
+        ),
+        ("https://t.me/my_bot?startgroup", DeepLink::start_group("my_bot")),
+        (
+            "https://t.me/my_bot?startgroup=abc&admin=change_info+post_messages+delete_messages",
+            DeepLink::start_group("my_bot")
+                .with_payload(payload.clone())
+                .unwrap()
+                .with_admin_rights(rights),
+        ),
+        (
+            "https://t.me/my_bot?startgroup",
+            DeepLink::start_group("my_bot").with_admin_rights(ChatAdministratorRights::default()),
+        ),
+        (
+            "https://t.me/my_bot?startchannel&admin=change_info+post_messages+delete_messages",
+            DeepLink::start_channel("my_bot", rights)
+                .with_payload(payload.clone())
+                .unwrap(),
+        ),
+        ("https://t.me/my_bot?startapp", DeepLink::start_app("my_bot")),
+        (
+            "https://t.me/my_bot/game?startapp=abc",
+            DeepLink::start_app("my_bot")
+                .with_app_name("game")
+                .with_payload(payload.clone())
+                .unwrap(),
+        ),
+        ("https://t.me/my_bot?startattach", DeepLink::start_attach("my_bot")),
+        (
+            "https://t.me/my_bot?startattach=abc",
+            DeepLink::start_attach("my_bot")
+                .with_payload(payload.clone())
+                .unwrap()
+                .with_admin_rights(rights),
+        ),
+    ] {
+        assert_eq!(link.to_string(), expected);
+    }
+
+    let payload = DeepLinkPayload::new("a".repeat(512)).unwrap();
+    assert_eq!(
+        DeepLink::start_app("my_bot")
+            .with_payload(payload.clone())
+            .unwrap()
+            .to_string(),
+        format!("https://t.me/my_bot?startapp={}", payload)
+    );
+    for result in [
+        DeepLink::start("my_bot", payload.clone()),
+        DeepLink::start_group("my_bot").with_payload(payload.clone()),
+        DeepLink::start_attach("my_bot").with_payload(payload),
+    ] {
+        assert!(matches!(
+            result.unwrap_err(),
+            DeepLinkError::PayloadTooLong { len: 512, max_len: 64 }
+        ));
+    }
+}
@@ -2,16 +2,23 @@ use std::{convert::TryFrom, error::Error, fmt, string::FromUtf16Error};
 
 use shellwords::MismatchedQuotes;
 
-use crate::types::Message;
+pub use self::typed::*;
+use crate::types::{Message, TextEntity};
 
 #[cfg(test)]
 mod tests;
 
+mod typed;
+
 /// A simple command implementation.
 ///
-/// We just take first command from a message and ignore others.
-/// Also we assume that entire text after command is arguments separated by whitespace.
-/// In order to include space in argument you need to wrap it with `'`: `'arg1 v' arg2`.
+/// Commands are read from the text or the caption of a message using bot command entities.
+/// Arguments of a command are the text after the command up to the next command.
+/// By default arguments are separated by whitespace;
+/// in order to include space in argument you need to wrap it with `'`: `'arg1 v' arg2`.
+///
+/// Use [`CommandParser`] to get all commands of a message,
+/// to skip commands addressed to other bots or to keep arguments raw.
 ///
 /// # Example
 /// ```
@@ -28,7 +35,10 @@ mod tests;
 #[derive(Clone, Debug)]
 pub struct Command {
     name: String,
+    bot_name: Option<String>,
     args: Vec<String>,
+    arg_ends: Vec<usize>,
+    raw_args: String,
     message: Message,
 }
 
@@ -38,17 +48,177 @@ impl Command {
         &self.name
     }
 
+    /// Returns the username of a bot the command is addressed to, without `@`.
+    pub fn get_bot_name(&self) -> Option<&str> {
+        self.bot_name.as_deref()
+    }
+
     /// Returns the list of arguments.
     pub fn get_args(&self) -> &[String] {
         &self.args
     }
 
+    /// Returns the arguments as they appear in the message, with surrounding whitespace trimmed.
+    pub fn get_raw_args(&self) -> &str {
+        &self.raw_args
+    }
+
     /// Returns the message where the command comes from.
     pub fn get_message(&self) -> &Message {
         &self.message
     }
 }
 
+/// Allows to configure parsing of commands.
+///
+/// # Example
+/// ```
+/// use tgbot::types::{CommandParser, Message};
+///
+/// fn handle_commands(message: Message) {
+///     let parser = CommandParser::default().with_bot_username("my_bot");
+///     for command in parser.parse_all(message).unwrap() {
+///         println!("NAME: {}", command.get_name());
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CommandParser {
+    bot_username: Option<String>,
+    raw_args: bool,
+}
+
+impl CommandParser {
+    /// Sets a new username of the bot.
+    ///
+    /// Commands addressed to other bots (e.g. `/start@other_bot`) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The username of the bot, see [`crate::types::Bot::username`].
+    pub fn with_bot_username<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let value = value.into();
+        self.bot_username = Some(String::from(value.trim_start_matches('@')));
+        self
+    }
+
+    /// Sets a new value for the `raw_args` flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Indicates whether to split arguments by whitespace only,
+    ///   without handling quotes; default - false.
+    pub fn with_raw_args(mut self, value: bool) -> Self {
+        self.raw_args = value;
+        self
+    }
+
+    /// Returns the first command of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to parse.
+    pub fn parse(&self, message: Message) -> Result<Command, CommandError> {
+        self.parse_commands(message, 1)?.pop().ok_or(CommandError::NotFound)
+    }
+
+    /// Returns all commands of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to parse.
+    pub fn parse_all(&self, message: Message) -> Result<Vec<Command>, CommandError> {
+        self.parse_commands(message, usize::MAX)
+    }
+
+    /// Returns the name of the first command of a message, e.g. `/start`.
+    ///
+    /// Arguments are not parsed, so the message is not cloned.
+    pub(crate) fn parse_name(&self, message: &Message) -> Option<String> {
+        self.find_commands(message, 1).ok()?.pop().map(|x| x.name)
+    }
+
+    fn parse_commands(&self, message: Message, limit: usize) -> Result<Vec<Command>, CommandError> {
+        let mut result = Vec::new();
+        for item in self.find_commands(&message, limit)? {
+            let args = if self.raw_args {
+                item.raw_args.split_whitespace().map(String::from).collect()
+            } else {
+                shellwords::split(&item.raw_args)?
+            };
+            let arg_ends = find_arg_ends(&item.raw_args, self.raw_args);
+            result.push(Command {
+                name: item.name,
+                bot_name: item.bot_name,
+                args,
+                arg_ends,
+                raw_args: item.raw_args,
+                message: message.clone(),
+            });
+        }
+        Ok(result)
+    }
+
+    fn find_commands(&self, message: &Message, limit: usize) -> Result<Vec<RawCommand>, CommandError> {
+        let text = match message.get_text() {
+            Some(text) => text,
+            None => return Ok(Vec::new()),
+        };
+        let data: Vec<u16> = text.data.encode_utf16().collect();
+        let mut positions: Vec<(usize, usize)> = text
+            .entities
+            .iter()
+            .flatten()
+            .filter_map(|entity| match entity {
+                TextEntity::BotCommand(position) => {
+                    let offset = position.offset as usize;
+                    Some((offset, offset + position.length as usize))
+                }
+                _ => None,
+            })
+            .filter(|(_, end)| *end <= data.len())
+            .collect();
+        positions.sort();
+        let mut result = Vec::new();
+        for (idx, &(start, end)) in positions.iter().enumerate() {
+            if result.len() == limit {
+                break;
+            }
+            let command = String::from_utf16(&data[start..end])?;
+            let (name, bot_name) = match command.split_once('@') {
+                Some((name, bot_name)) => (String::from(name), Some(String::from(bot_name))),
+                None => (command, None),
+            };
+            if let (Some(expected), Some(actual)) = (&self.bot_username, &bot_name) {
+                if !expected.eq_ignore_ascii_case(actual) {
+                    continue;
+                }
+            }
+            let args_end = positions
+                .get(idx + 1)
+                .map(|(next_start, _)| *next_start)
+                .unwrap_or(data.len())
+                .max(end);
+            let raw_args = String::from_utf16(&data[end..args_end])?;
+            result.push(RawCommand {
+                name,
+                bot_name,
+                raw_args: String::from(raw_args.trim()),
+            });
+        }
+        Ok(result)
+    }
+}
+
+struct RawCommand {
+    name: String,
+    bot_name: Option<String>,
+    raw_args: String,
+}
+
 /// Represents an error when parsing a command.
 #[derive(Debug)]
 pub enum CommandError {
@@ -96,27 +266,53 @@ impl fmt::Display for CommandError {
     }
 }
 
+/// Returns byte offsets where arguments end in a raw arguments string.
+///
+/// Quotes are expected to be matched, since the string is already split into arguments.
+fn find_arg_ends(raw_args: &str, is_raw: bool) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut chars = raw_args.char_indices().peekable();
+    loop {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut end = raw_args.len();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {
+                    end = idx;
+                    break;
+                }
+                '\\' if !is_raw => {
+                    chars.next();
+                }
+                '\'' if !is_raw => {
+                    chars.find(|(_, c)| *c == '\'');
+                }
+                '"' if !is_raw => {
+                    while let Some((_, c)) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        result.push(end);
+    }
+    result
+}
+
 impl TryFrom<Message> for Command {
     type Error = CommandError;
 
     fn try_from(message: Message) -> Result<Self, Self::Error> {
-        match message.get_text().map(|text| (text.get_bot_commands(), text)) {
-            Some((Some(commands), text)) => {
-                // just take first command and ignore others
-                let command = &commands[0];
-                let name = command.command.clone();
-                // assume that all text after command is arguments
-                let offset = text.data.find(&name).unwrap_or(0);
-                // bot suffix is 1 character longer due to '@' symbol
-                let length = name.len() + command.bot_name.as_ref().map(|x| x.len() + 1).unwrap_or(0);
-                let pos = offset + length;
-                // pos is UTF-16 offset
-                let raw_args: Vec<u16> = text.data.encode_utf16().skip(pos).collect();
-                let raw_args = String::from_utf16(&raw_args)?;
-                let args = shellwords::split(&raw_args)?;
-                Ok(Command { name, args, message })
-            }
-            _ => Err(CommandError::NotFound),
-        }
+        CommandParser::default().parse(message)
     }
 }
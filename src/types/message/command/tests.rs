@@ -1,4 +1,4 @@
-use crate::types::{Command, Message};
+use crate::types::{Command, CommandError, CommandParser, Message};
 
 fn create_command(command: &str) -> Command {
     let len = command.split_whitespace().next().unwrap().len();
@@ -49,3 +49,110 @@ fn command_bot_suffix_no_args() {
     assert!(command.get_args().is_empty());
     assert_eq!(command.get_message().id, 1111);
 }
+
+fn create_message(data: serde_json::Value) -> Message {
+    let mut value = serde_json::json!({
+        "message_id": 1111,
+        "date": 0,
+        "from": {"id": 1, "is_bot": false, "first_name": "test"},
+        "chat": {"id": 1, "type": "private", "first_name": "test"}
+    });
+    for (key, item) in data.as_object().unwrap() {
+        value[key] = item.clone();
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn command_non_ascii_prefix() {
+    let message = create_message(serde_json::json!({
+        "text": "Привет 👋 /test_command arg",
+        "entities": [
+            {"type": "bot_command", "offset": 10, "length": 13}
+        ]
+    }));
+    let command = Command::try_from(message).unwrap();
+    assert_eq!(command.get_name(), "/test_command");
+    assert_eq!(command.get_args(), &["arg"]);
+}
+
+#[test]
+fn command_caption() {
+    let message = create_message(serde_json::json!({
+        "photo": [{"file_id": "photo-id", "file_unique_id": "photo-uid", "width": 1, "height": 1}],
+        "caption": "/test_command arg",
+        "caption_entities": [
+            {"type": "bot_command", "offset": 0, "length": 13}
+        ]
+    }));
+    let command = Command::try_from(message).unwrap();
+    assert_eq!(command.get_name(), "/test_command");
+    assert_eq!(command.get_args(), &["arg"]);
+}
+
+#[test]
+fn command_parser_all() {
+    let message = create_message(serde_json::json!({
+        "text": "/first a 'b c' /second@other_bot d\n/third@My_Bot 'e",
+        "entities": [
+            {"type": "bot_command", "offset": 15, "length": 17},
+            {"type": "bot_command", "offset": 0, "length": 6},
+            {"type": "bot_command", "offset": 35, "length": 13}
+        ]
+    }));
+
+    let commands = CommandParser::default()
+        .with_raw_args(true)
+        .parse_all(message.clone())
+        .unwrap();
+    let commands: Vec<_> = commands
+        .iter()
+        .map(|x| (x.get_name(), x.get_bot_name(), x.get_args(), x.get_raw_args()))
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            (
+                "/first",
+                None,
+                &[String::from("a"), String::from("'b"), String::from("c'")][..],
+                "a 'b c'"
+            ),
+            ("/second", Some("other_bot"), &[String::from("d")][..], "d"),
+            ("/third", Some("My_Bot"), &[String::from("'e")][..], "'e"),
+        ]
+    );
+
+    let parser = CommandParser::default().with_bot_username("@my_bot");
+    let err = parser.parse_all(message.clone()).unwrap_err();
+    assert!(matches!(err, CommandError::MismatchedQuotes(_)));
+    assert_eq!(parser.parse_name(&message).unwrap(), "/first");
+
+    let parser = parser.with_raw_args(true);
+    let names: Vec<_> = parser
+        .parse_all(message.clone())
+        .unwrap()
+        .into_iter()
+        .map(|x| String::from(x.get_name()))
+        .collect();
+    assert_eq!(names, vec!["/first", "/third"]);
+
+    let command = CommandParser::default().parse(message).unwrap();
+    assert_eq!(command.get_args(), &["a", "b c"]);
+}
+
+#[test]
+fn command_not_found() {
+    let message = create_message(serde_json::json!({"text": "/start"}));
+    assert!(matches!(Command::try_from(message), Err(CommandError::NotFound)));
+
+    let message = create_message(serde_json::json!({
+        "text": "/start@other_bot",
+        "entities": [
+            {"type": "bot_command", "offset": 0, "length": 16}
+        ]
+    }));
+    let parser = CommandParser::default().with_bot_username("my_bot");
+    assert!(parser.parse_name(&message).is_none());
+    assert!(matches!(parser.parse(message), Err(CommandError::NotFound)));
+}
@@ -0,0 +1,397 @@
+use std::{collections::BTreeMap, error::Error, fmt, str::FromStr};
+
+use crate::types::{BotCommand, BotCommandError, BotCommandScope, Command, SetBotCommands};
+
+#[cfg(test)]
+mod tests;
+
+/// Represents a set of commands with typed arguments.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::types::{CommandArgs, CommandDescriptor, TypedCommand, TypedCommandError};
+///
+/// enum BotCommands {
+///     Start,
+///     Roll { sides: u32, label: Option<String> },
+/// }
+///
+/// impl TypedCommand for BotCommands {
+///     fn get_descriptors() -> Vec<CommandDescriptor> {
+///         vec![
+///             CommandDescriptor::new("start", "Start the bot"),
+///             CommandDescriptor::new("roll", "Roll a die")
+///                 .with_usage("<sides> [label]")
+///                 .with_translation("de", "Würfeln"),
+///         ]
+///     }
+///
+///     fn from_args(name: &str, args: &mut CommandArgs) -> Result<Self, TypedCommandError> {
+///         match name {
+///             "start" => Ok(Self::Start),
+///             "roll" => Ok(Self::Roll {
+///                 sides: args.next("sides")?,
+///                 label: args.rest(),
+///             }),
+///             _ => Err(TypedCommandError::UnknownCommand(String::from(name))),
+///         }
+///     }
+/// }
+/// ```
+pub trait TypedCommand: Sized {
+    /// Returns descriptors of the commands.
+    fn get_descriptors() -> Vec<CommandDescriptor>;
+
+    /// Creates a command from arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the command without leading slash.
+    /// * `args` - Arguments of the command.
+    fn from_args(name: &str, args: &mut CommandArgs) -> Result<Self, TypedCommandError>;
+
+    /// Parses a command.
+    ///
+    /// Fails when the command has arguments not consumed by [`TypedCommand::from_args`].
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command to parse.
+    fn parse(command: &Command) -> Result<Self, TypedCommandError> {
+        let name = command.get_name().trim_start_matches('/');
+        let mut args = CommandArgs::from_command(command);
+        let result = Self::from_args(name, &mut args)?;
+        args.finish()?;
+        Ok(result)
+    }
+}
+
+/// Allows to read typed arguments of a command.
+#[derive(Clone, Debug)]
+pub struct CommandArgs<'a> {
+    items: &'a [String],
+    position: usize,
+    raw: Option<(&'a str, &'a [usize])>,
+}
+
+impl<'a> CommandArgs<'a> {
+    /// Creates a new `CommandArgs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Arguments of a command.
+    pub fn new(items: &'a [String]) -> Self {
+        Self {
+            items,
+            position: 0,
+            raw: None,
+        }
+    }
+
+    pub(crate) fn from_command(command: &'a Command) -> Self {
+        let raw = Some((command.get_raw_args(), command.arg_ends.as_slice()))
+            .filter(|(_, ends)| ends.len() == command.get_args().len());
+        Self {
+            items: command.get_args(),
+            position: 0,
+            raw,
+        }
+    }
+
+    /// Returns the next required argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the argument used in error messages.
+    pub fn next<T>(&mut self, name: &str) -> Result<T, TypedCommandError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.next_optional(name)?
+            .ok_or_else(|| TypedCommandError::MissingArgument(String::from(name)))
+    }
+
+    /// Returns the next optional argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the argument used in error messages.
+    pub fn next_optional<T>(&mut self, name: &str) -> Result<Option<T>, TypedCommandError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = match self.items.get(self.position) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        self.position += 1;
+        value
+            .parse()
+            .map(Some)
+            .map_err(|err: T::Err| TypedCommandError::InvalidArgument {
+                name: String::from(name),
+                value: value.clone(),
+                message: err.to_string(),
+            })
+    }
+
+    /// Returns all remaining arguments as they appear in the message.
+    ///
+    /// Spacing, quotes and line breaks are kept;
+    /// when the arguments are created using [`CommandArgs::new`], they are joined with a space.
+    ///
+    /// Returns `None` when there are no remaining arguments.
+    pub fn rest(&mut self) -> Option<String> {
+        let start = self.position;
+        let items = &self.items[start..];
+        self.position = self.items.len();
+        if items.is_empty() {
+            return None;
+        }
+        Some(match self.raw {
+            Some((raw_args, ends)) => {
+                let offset = start.checked_sub(1).map(|idx| ends[idx]).unwrap_or(0);
+                String::from(raw_args[offset..].trim_start())
+            }
+            None => items.join(" "),
+        })
+    }
+
+    /// Ensures that all arguments were consumed.
+    pub fn finish(self) -> Result<(), TypedCommandError> {
+        match self.items.get(self.position) {
+            Some(value) => Err(TypedCommandError::UnexpectedArgument(value.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Represents an error when parsing a typed command.
+#[derive(Debug)]
+pub enum TypedCommandError {
+    /// An argument has an invalid value.
+    InvalidArgument {
+        /// The name of the argument.
+        name: String,
+        /// The value of the argument.
+        value: String,
+        /// The error message.
+        message: String,
+    },
+    /// A required argument is missing.
+    MissingArgument(String),
+    /// An argument is not expected.
+    UnexpectedArgument(String),
+    /// A command is not known.
+    UnknownCommand(String),
+}
+
+impl Error for TypedCommandError {}
+
+impl fmt::Display for TypedCommandError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::TypedCommandError::*;
+        match self {
+            InvalidArgument { name, value, message } => {
+                write!(out, "invalid value '{}' for argument <{}>: {}", value, name, message)
+            }
+            MissingArgument(name) => write!(out, "missing argument <{}>", name),
+            UnexpectedArgument(value) => write!(out, "unexpected argument '{}'", value),
+            UnknownCommand(name) => write!(out, "unknown command /{}", name),
+        }
+    }
+}
+
+/// Describes a command advertised to users.
+#[derive(Clone, Debug)]
+pub struct CommandDescriptor {
+    name: String,
+    description: String,
+    scopes: Vec<BotCommandScope>,
+    translations: BTreeMap<String, String>,
+    usage: Option<String>,
+}
+
+impl CommandDescriptor {
+    /// Creates a new `CommandDescriptor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the command without leading slash.
+    /// * `description` - The default description of the command.
+    pub fn new<A, B>(name: A, description: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            scopes: Vec::new(),
+            translations: BTreeMap::new(),
+            usage: None,
+        }
+    }
+
+    /// Adds a scope where the command is advertised.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The scope; default - [`BotCommandScope::Default`].
+    pub fn with_scope(mut self, value: BotCommandScope) -> Self {
+        self.scopes.push(value);
+        self
+    }
+
+    /// Adds a description for users with the given language.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_code` - Two-letter ISO 639-1 language code.
+    /// * `description` - The description of the command.
+    pub fn with_translation<A, B>(mut self, language_code: A, description: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        self.translations.insert(language_code.into(), description.into());
+        self
+    }
+
+    /// Sets a new usage.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Arguments of the command shown in the help text, e.g. `<sides> [label]`.
+    pub fn with_usage<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.usage = Some(value.into());
+        self
+    }
+
+    /// Returns the name of the command.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description of the command.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_code` - Language of the description; the default description is used as a fallback.
+    pub fn description(&self, language_code: Option<&str>) -> &str {
+        language_code
+            .and_then(|x| self.translations.get(x))
+            .unwrap_or(&self.description)
+    }
+
+    fn is_advertised_in(&self, scope: &BotCommandScope) -> bool {
+        if self.scopes.is_empty() {
+            matches!(scope, BotCommandScope::Default)
+        } else {
+            self.scopes.contains(scope)
+        }
+    }
+}
+
+/// Produces lists of commands and a help text from command descriptors.
+///
+/// Allows to keep advertised commands in sync with the commands a bot can parse.
+#[derive(Clone, Debug, Default)]
+pub struct CommandRegistry {
+    descriptors: Vec<CommandDescriptor>,
+}
+
+impl CommandRegistry {
+    /// Adds a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Descriptor of the command.
+    pub fn with_command(mut self, value: CommandDescriptor) -> Self {
+        self.descriptors.push(value);
+        self
+    }
+
+    /// Adds commands of a [`TypedCommand`].
+    pub fn with_commands<T>(mut self) -> Self
+    where
+        T: TypedCommand,
+    {
+        self.descriptors.extend(T::get_descriptors());
+        self
+    }
+
+    /// Returns methods setting lists of commands for every scope and language.
+    ///
+    /// A list with default descriptions is created for every scope,
+    /// and a list for every language having a translation for any command in the scope.
+    pub fn get_set_bot_commands(&self) -> Result<Vec<SetBotCommands>, BotCommandError> {
+        let mut scopes: Vec<BotCommandScope> = Vec::new();
+        for descriptor in &self.descriptors {
+            let descriptor_scopes = if descriptor.scopes.is_empty() {
+                vec![BotCommandScope::Default]
+            } else {
+                descriptor.scopes.clone()
+            };
+            for scope in descriptor_scopes {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+        }
+        let mut result = Vec::new();
+        for scope in scopes {
+            let descriptors: Vec<&CommandDescriptor> =
+                self.descriptors.iter().filter(|x| x.is_advertised_in(&scope)).collect();
+            let mut language_codes: Vec<Option<&str>> = vec![None];
+            for descriptor in &descriptors {
+                for language_code in descriptor.translations.keys() {
+                    let language_code = Some(language_code.as_str());
+                    if !language_codes.contains(&language_code) {
+                        language_codes.push(language_code);
+                    }
+                }
+            }
+            for language_code in language_codes {
+                let commands = descriptors
+                    .iter()
+                    .map(|x| BotCommand::new(x.name.clone(), x.description(language_code)))
+                    .collect::<Result<Vec<BotCommand>, BotCommandError>>()?;
+                let mut method = SetBotCommands::new(commands).with_scope(scope.clone());
+                if let Some(language_code) = language_code {
+                    method = method.with_language_code(language_code);
+                }
+                result.push(method);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns a help text listing all commands.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_code` - Language of descriptions; the default description is used as a fallback.
+    pub fn get_help_text(&self, language_code: Option<&str>) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for descriptor in &self.descriptors {
+            let mut line = format!("/{}", descriptor.name);
+            if let Some(ref usage) = descriptor.usage {
+                line.push(' ');
+                line.push_str(usage);
+            }
+            line.push_str(" - ");
+            line.push_str(descriptor.description(language_code));
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,208 @@
+use serde_json::json;
+
+use crate::types::{
+    BotCommandScope,
+    Command,
+    CommandArgs,
+    CommandDescriptor,
+    CommandParser,
+    CommandRegistry,
+    Message,
+    TypedCommand,
+    TypedCommandError,
+};
+
+#[derive(Debug, PartialEq)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "slow" => Ok(Self::Slow),
+            _ => Err(String::from("expected fast or slow")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TestCommand {
+    Start,
+    Roll { sides: u32, mode: Option<Mode> },
+    Say(Option<String>),
+}
+
+impl TypedCommand for TestCommand {
+    fn get_descriptors() -> Vec<CommandDescriptor> {
+        vec![
+            CommandDescriptor::new("start", "Start the bot").with_translation("de", "Bot starten"),
+            CommandDescriptor::new("roll", "Roll a die")
+                .with_usage("<sides> [mode]")
+                .with_scope(BotCommandScope::Default)
+                .with_scope(BotCommandScope::AllPrivateChats),
+            CommandDescriptor::new("say", "Say something")
+                .with_usage("[text]")
+                .with_scope(BotCommandScope::AllGroupChats),
+        ]
+    }
+
+    fn from_args(name: &str, args: &mut CommandArgs) -> Result<Self, TypedCommandError> {
+        match name {
+            "start" => Ok(Self::Start),
+            "roll" => Ok(Self::Roll {
+                sides: args.next("sides")?,
+                mode: args.next_optional("mode")?,
+            }),
+            "say" => Ok(Self::Say(args.rest())),
+            _ => Err(TypedCommandError::UnknownCommand(String::from(name))),
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<TestCommand, TypedCommandError> {
+    let len = text.split_whitespace().next().unwrap().len();
+    let message: Message = serde_json::from_value(json!({
+        "message_id": 1,
+        "date": 0,
+        "from": {"id": 1, "is_bot": false, "first_name": "test"},
+        "chat": {"id": 1, "type": "private", "first_name": "test"},
+        "text": text,
+        "entities": [
+            {"type": "bot_command", "offset": 0, "length": len}
+        ]
+    }))
+    .unwrap();
+    TestCommand::parse(&Command::try_from(message).unwrap())
+}
+
+#[test]
+fn typed_command() {
+    assert_eq!(parse("/start").unwrap(), TestCommand::Start);
+    assert_eq!(parse("/roll 6").unwrap(), TestCommand::Roll { sides: 6, mode: None });
+    assert_eq!(
+        parse("/roll 20 slow").unwrap(),
+        TestCommand::Roll {
+            sides: 20,
+            mode: Some(Mode::Slow)
+        }
+    );
+    assert_eq!(
+        parse("/say hello  'big world'").unwrap(),
+        TestCommand::Say(Some(String::from("hello  'big world'")))
+    );
+    assert_eq!(parse("/say").unwrap(), TestCommand::Say(None));
+}
+
+#[test]
+fn command_args_rest() {
+    for (text, raw_args, expected) in [
+        ("/note 1 first  line\nsecond", false, "first  line\nsecond"),
+        ("/note 1 'a b' \"c\\\" d\" e", false, "'a b' \"c\\\" d\" e"),
+        ("/note 'a b'  c", false, "c"),
+        ("/note it's raw", true, "raw"),
+    ] {
+        let message: Message = serde_json::from_value(json!({
+            "message_id": 1,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": {"id": 1, "type": "private", "first_name": "test"},
+            "text": text,
+            "entities": [
+                {"type": "bot_command", "offset": 0, "length": 5}
+            ]
+        }))
+        .unwrap();
+        let command = CommandParser::default().with_raw_args(raw_args).parse(message).unwrap();
+        let mut args = CommandArgs::from_command(&command);
+        args.next::<String>("first").unwrap();
+        assert_eq!(args.rest().unwrap(), expected);
+        assert!(args.rest().is_none());
+    }
+
+    let items = [String::from("a"), String::from("b")];
+    assert_eq!(CommandArgs::new(&items).rest().unwrap(), "a b");
+}
+
+#[test]
+fn typed_command_errors() {
+    for (text, expected) in [
+        ("/roll", "missing argument <sides>"),
+        (
+            "/roll x",
+            "invalid value 'x' for argument <sides>: invalid digit found in string",
+        ),
+        (
+            "/roll 6 medium",
+            "invalid value 'medium' for argument <mode>: expected fast or slow",
+        ),
+        ("/roll 6 fast extra", "unexpected argument 'extra'"),
+        ("/start now", "unexpected argument 'now'"),
+        ("/stop", "unknown command /stop"),
+    ] {
+        assert_eq!(parse(text).unwrap_err().to_string(), expected);
+    }
+}
+
+#[test]
+fn command_registry_set_bot_commands() {
+    let registry = CommandRegistry::default().with_commands::<TestCommand>();
+    let methods: Vec<_> = registry
+        .get_set_bot_commands()
+        .unwrap()
+        .into_iter()
+        .map(|x| serde_json::to_value(x).unwrap())
+        .collect();
+    assert_eq!(
+        methods,
+        vec![
+            json!({
+                "commands": [
+                    {"command": "start", "description": "Start the bot"},
+                    {"command": "roll", "description": "Roll a die"}
+                ],
+                "scope": {"type": "default"}
+            }),
+            json!({
+                "commands": [
+                    {"command": "start", "description": "Bot starten"},
+                    {"command": "roll", "description": "Roll a die"}
+                ],
+                "language_code": "de",
+                "scope": {"type": "default"}
+            }),
+            json!({
+                "commands": [
+                    {"command": "roll", "description": "Roll a die"}
+                ],
+                "scope": {"type": "all_private_chats"}
+            }),
+            json!({
+                "commands": [
+                    {"command": "say", "description": "Say something"}
+                ],
+                "scope": {"type": "all_group_chats"}
+            }),
+        ]
+    );
+
+    let registry = registry.with_command(CommandDescriptor::new("x", "x"));
+    assert!(registry.get_set_bot_commands().is_err());
+}
+
+#[test]
+fn command_registry_help_text() {
+    let registry = CommandRegistry::default().with_commands::<TestCommand>();
+    assert_eq!(
+        registry.get_help_text(None),
+        "/start - Start the bot\n/roll <sides> [mode] - Roll a die\n/say [text] - Say something"
+    );
+    assert_eq!(
+        registry.get_help_text(Some("de")),
+        "/start - Bot starten\n/roll <sides> [mode] - Roll a die\n/say [text] - Say something"
+    );
+}
@@ -16,6 +16,7 @@ use crate::{
         ParseMode,
         ReplyMarkup,
         ReplyParameters,
+        Text,
         TextEntities,
         TextEntity,
     },
@@ -1394,6 +1395,56 @@ impl SendMessage {
         self.reply_parameters = Some(value);
         self
     }
+
+    /// Splits the message into messages fitting into [`Text::MAX_MESSAGE_LEN`].
+    ///
+    /// A text using HTML or MarkdownV2 parse mode is parsed locally;
+    /// the message is left as is when the markup is invalid, another parse mode is used
+    /// or the text consists of whitespace only.
+    ///
+    /// Reply parameters and a message effect are kept in the first message,
+    /// and a reply markup is kept in the last one.
+    pub(crate) fn split(self) -> Vec<Self> {
+        let text = match self.parse_mode {
+            None => Text {
+                data: self.text.clone(),
+                entities: self.entities.clone(),
+            },
+            Some(ParseMode::Html) => match Text::from_html(&self.text) {
+                Ok(text) => text,
+                Err(_) => return vec![self],
+            },
+            Some(ParseMode::MarkdownV2) => match Text::from_markdown_v2(&self.text) {
+                Ok(text) => text,
+                Err(_) => return vec![self],
+            },
+            Some(ParseMode::Markdown) => return vec![self],
+        };
+        let chunks = text.split(Text::MAX_MESSAGE_LEN);
+        // a whitespace-only text has no chunks, let the Bot API report it
+        if chunks.is_empty() {
+            return vec![self];
+        }
+        let last_idx = chunks.len() - 1;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(idx, chunk)| {
+                let mut method = self.clone();
+                method.text = chunk.data;
+                method.entities = chunk.entities;
+                method.parse_mode = None;
+                if idx != 0 {
+                    method.message_effect_id = None;
+                    method.reply_parameters = None;
+                }
+                if idx != last_idx {
+                    method.reply_markup = None;
+                }
+                method
+            })
+            .collect()
+    }
 }
 
 impl Method for SendMessage {
@@ -536,6 +536,69 @@ fn send_message() {
     );
 }
 
+#[test]
+fn send_message_split() {
+    let text = format!("<b>{}\n\n{}</b>", "a".repeat(4000), "b".repeat(100));
+    let methods = SendMessage::new(1, text)
+        .with_message_effect_id("effect-id")
+        .with_message_thread_id(1)
+        .with_parse_mode(ParseMode::Html)
+        .with_reply_markup(ForceReply::new(true))
+        .with_reply_parameters(ReplyParameters::new(1))
+        .split();
+    assert_eq!(methods.len(), 2);
+    assert_payload_eq(
+        Payload::json(
+            "sendMessage",
+            serde_json::json!({
+                "chat_id": 1,
+                "text": "a".repeat(4000),
+                "entities": [{
+                    "type": "bold",
+                    "offset": 0,
+                    "length": 4000
+                }],
+                "message_effect_id": "effect-id",
+                "message_thread_id": 1,
+                "reply_parameters": {
+                    "message_id": 1
+                }
+            }),
+        ),
+        methods[0].clone(),
+    );
+    assert_payload_eq(
+        Payload::json(
+            "sendMessage",
+            serde_json::json!({
+                "chat_id": 1,
+                "text": "b".repeat(100),
+                "entities": [{
+                    "type": "bold",
+                    "offset": 0,
+                    "length": 100
+                }],
+                "message_thread_id": 1,
+                "reply_markup": {
+                    "force_reply": true
+                }
+            }),
+        ),
+        methods[1].clone(),
+    );
+
+    let method = SendMessage::new(1, "<b>text").with_parse_mode(ParseMode::Html);
+    assert_eq!(method.split().len(), 1);
+
+    let text = " ".repeat(5000);
+    let methods = SendMessage::new(1, text.clone()).split();
+    assert_eq!(methods.len(), 1);
+    assert_payload_eq(
+        Payload::json("sendMessage", serde_json::json!({"chat_id": 1, "text": text})),
+        methods[0].clone(),
+    );
+}
+
 #[test]
 fn stop_message_live_location() {
     assert_payload_eq(
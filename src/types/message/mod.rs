@@ -208,6 +208,11 @@ impl Message {
         self.edit_date.is_some()
     }
 
+    /// Returns the quote of the message with the message it refers to.
+    pub fn get_quote(&self) -> Option<MessageQuote<'_>> {
+        MessageQuote::new(self)
+    }
+
     /// Returns a text of the message (includes caption).
     pub fn get_text(&self) -> Option<&Text> {
         match self.data {
@@ -1,6 +1,8 @@
+use std::ops::Range;
+
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::types::{Integer, Text, TextEntities};
+use crate::types::{ExternalReplyInfo, Integer, Message, ReplyTo, Text, TextEntities};
 
 #[cfg(test)]
 mod tests;
@@ -52,6 +54,55 @@ impl TextQuote {
     }
 }
 
+/// Represents a quote of an incoming reply together with the message it refers to.
+///
+/// Use [`Message::get_quote`] to get a quote.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageQuote<'a> {
+    /// The quoted part of the original message.
+    pub quote: &'a TextQuote,
+    /// The original message, if it is in the same chat.
+    pub reply_to: Option<&'a Message>,
+    /// Information about the original message, if it is from another chat or forum topic.
+    pub external_reply: Option<&'a ExternalReplyInfo>,
+}
+
+impl<'a> MessageQuote<'a> {
+    pub(crate) fn new(message: &'a Message) -> Option<Self> {
+        message.quote.as_ref().map(|quote| Self {
+            quote,
+            reply_to: match message.reply_to {
+                Some(ReplyTo::Message(ref message)) => Some(message),
+                _ => None,
+            },
+            external_reply: message.external_reply.as_ref(),
+        })
+    }
+
+    /// Returns the text or caption of the original message.
+    pub fn get_original_text(&self) -> Option<&'a Text> {
+        self.reply_to.and_then(Message::get_text)
+    }
+
+    /// Returns the range of the quote in the text of the original message in bytes.
+    ///
+    /// Since the position of a quote is approximate,
+    /// the occurrence closest to it is returned.
+    pub fn find_range(&self) -> Option<Range<usize>> {
+        let data = &self.get_original_text()?.data;
+        let value = &self.quote.text.data;
+        if value.is_empty() {
+            return None;
+        }
+        data.match_indices(value.as_str())
+            .min_by_key(|(idx, _)| {
+                let position = data[..*idx].encode_utf16().count() as Integer;
+                (position - self.quote.position).abs()
+            })
+            .map(|(idx, _)| idx..idx + value.len())
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 struct RawText {
     text: String,
@@ -1,4 +1,14 @@
-use crate::types::{tests::assert_json_eq, Text, TextEntities, TextEntity, TextQuote};
+use crate::types::{
+    tests::assert_json_eq,
+    Message,
+    MessageData,
+    SupergroupChat,
+    Text,
+    TextEntities,
+    TextEntity,
+    TextQuote,
+    User,
+};
 
 #[test]
 fn text_quote() {
@@ -29,3 +39,35 @@ fn text_quote() {
         }),
     );
 }
+
+#[test]
+fn message_quote() {
+    let create_message = |id, text| {
+        Message::new(
+            id,
+            0,
+            SupergroupChat::new(1, "test"),
+            MessageData::Text(Text::from(text)),
+            User::new(1, "test", false),
+        )
+    };
+    let original = create_message(1, "hello world, hello");
+    let reply = create_message(2, "reply");
+    assert!(reply.get_quote().is_none());
+
+    let reply = reply
+        .with_quote(TextQuote::new(12, "hello"))
+        .with_reply_to(original.clone());
+    let quote = reply.get_quote().unwrap();
+    assert_eq!(quote.quote, &TextQuote::new(12, "hello"));
+    assert_eq!(quote.reply_to, Some(&original));
+    assert!(quote.external_reply.is_none());
+    assert_eq!(quote.get_original_text(), original.get_text());
+    assert_eq!(quote.find_range(), Some(13..18));
+
+    let reply = create_message(2, "reply").with_quote(TextQuote::new(0, "missing"));
+    let quote = reply.get_quote().unwrap();
+    assert!(quote.reply_to.is_none());
+    assert!(quote.get_original_text().is_none());
+    assert!(quote.find_range().is_none());
+}
@@ -8,6 +8,7 @@ pub use self::{
     chat::*,
     color::*,
     contact::*,
+    deep_link::*,
     dice::*,
     file::*,
     forum::*,
@@ -44,6 +45,7 @@ mod callback;
 mod chat;
 mod color;
 mod contact;
+mod deep_link;
 mod dice;
 mod file;
 mod forum;
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 mod tests;
 
 const ESCAPE_MARKDOWN: [char; 4] = ['_', '*', '`', '['];
-const ESCAPE_MARKDOWN_V2: [char; 18] = [
+pub(crate) const ESCAPE_MARKDOWN_V2: [char; 18] = [
     '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!',
 ];
 
@@ -27,54 +27,105 @@ pub enum ParseMode {
 }
 
 impl ParseMode {
-    /// Escapes HTML or Markdown special characters.
+    /// Escapes HTML or Markdown special characters in a plain text.
     ///
     /// # Arguments
     ///
     /// * `input` - String that will be escaped.
     ///
-    /// For MarkdownV2 you must escape characters with `\` by yourself in the following cases:
+    /// Use [`Self::escape_code`] inside `code` and `pre` entities
+    /// and [`Self::escape_link_url`] inside URLs of links.
+    pub fn escape<T>(self, input: T) -> String
+    where
+        T: Into<String>,
+    {
+        let input = input.into();
+        match self {
+            ParseMode::Html => escape_html(&input, false),
+            ParseMode::Markdown => escape_with_backslash(&input, |c| ESCAPE_MARKDOWN.contains(&c)),
+            ParseMode::MarkdownV2 => escape_with_backslash(&input, |c| c == '\\' || ESCAPE_MARKDOWN_V2.contains(&c)),
+        }
+    }
+
+    /// Escapes special characters inside `code` and `pre` entities.
     ///
-    /// * all \` and `\` characters in 'pre' and 'code'.
-    /// * `(...)` - part of inline link definition, all `)` and `\` characters.
+    /// # Arguments
     ///
-    /// In all other places use this method to escape special characters.
-    pub fn escape<T>(self, input: T) -> String
+    /// * `input` - String that will be escaped.
+    ///
+    /// Markdown does not allow to escape characters inside entities,
+    /// so the input is returned unchanged.
+    pub fn escape_code<T>(self, input: T) -> String
     where
         T: Into<String>,
     {
         let input = input.into();
-        let mut result = String::new();
         match self {
-            ParseMode::Html => {
-                for i in input.chars() {
-                    match i {
-                        '<' => result += "&lt;",
-                        '>' => result += "&gt;",
-                        '&' => result += "&amp;",
-                        _ => result.push(i),
-                    }
-                }
-            }
-            ParseMode::Markdown => {
-                for i in input.chars() {
-                    if ESCAPE_MARKDOWN.contains(&i) {
-                        result.push('\\');
-                    }
-                    result.push(i);
-                }
-            }
-            ParseMode::MarkdownV2 => {
-                for i in input.chars() {
-                    if ESCAPE_MARKDOWN_V2.contains(&i) {
-                        result.push('\\');
-                    }
-                    result.push(i);
-                }
-            }
+            ParseMode::Html => escape_html(&input, false),
+            ParseMode::Markdown => input,
+            ParseMode::MarkdownV2 => escape_with_backslash(&input, |c| matches!(c, '`' | '\\')),
+        }
+    }
+
+    /// Escapes special characters inside a URL of a link.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - URL that will be escaped.
+    ///
+    /// Markdown does not allow to escape characters inside entities,
+    /// so the input is returned unchanged.
+    pub fn escape_link_url<T>(self, input: T) -> String
+    where
+        T: Into<String>,
+    {
+        let input = input.into();
+        match self {
+            ParseMode::Html => Self::escape_html_attribute(input),
+            ParseMode::Markdown => input,
+            ParseMode::MarkdownV2 => escape_with_backslash(&input, |c| matches!(c, ')' | '\\')),
+        }
+    }
+
+    /// Escapes special characters inside a double-quoted HTML attribute value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - String that will be escaped.
+    pub fn escape_html_attribute<T>(input: T) -> String
+    where
+        T: Into<String>,
+    {
+        escape_html(&input.into(), true)
+    }
+}
+
+fn escape_html(input: &str, is_attribute: bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '"' if is_attribute => result.push_str("&quot;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn escape_with_backslash<F>(input: &str, is_special: F) -> String
+where
+    F: Fn(char) -> bool,
+{
+    let mut result = String::with_capacity(input.len());
+    for c in input.chars() {
+        if is_special(c) {
+            result.push('\\');
         }
-        result
+        result.push(c);
     }
+    result
 }
 
 impl fmt::Display for ParseMode {
@@ -27,3 +27,30 @@ fn parse_mode_escape() {
         r"\_\*\[\]\(\)\~\`\>\#\+\-\=\|\{\}\.\!"
     );
 }
+
+#[test]
+fn parse_mode_escape_context() {
+    assert_eq!(ParseMode::MarkdownV2.escape(r"\_"), r"\\\_");
+    assert_eq!(ParseMode::Html.escape(r#""'"#), r#""'"#);
+
+    let code = r"`a\b` <c> *d*";
+    assert_eq!(ParseMode::Html.escape_code(code), r"`a\b` &lt;c&gt; *d*");
+    assert_eq!(ParseMode::Markdown.escape_code(code), code);
+    assert_eq!(ParseMode::MarkdownV2.escape_code(code), r"\`a\\b\` <c> *d*");
+
+    let url = r#"https://t.me/(a)?b="c"&d=\"#;
+    assert_eq!(
+        ParseMode::Html.escape_link_url(url),
+        r"https://t.me/(a)?b=&quot;c&quot;&amp;d=\"
+    );
+    assert_eq!(ParseMode::Markdown.escape_link_url(url), url);
+    assert_eq!(
+        ParseMode::MarkdownV2.escape_link_url(url),
+        r#"https://t.me/(a\)?b="c"&d=\\"#
+    );
+
+    assert_eq!(
+        ParseMode::escape_html_attribute(r#"<a href="b">&"#),
+        "&lt;a href=&quot;b&quot;&gt;&amp;"
+    );
+}
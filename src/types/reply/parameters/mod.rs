@@ -1,9 +1,9 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, ops::Range};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Error as JsonError;
 
-use crate::types::{ChatId, Integer, ParseMode, TextEntities, TextEntity};
+use crate::types::{ChatId, Integer, Message, ParseMode, Text, TextEntities, TextEntity};
 
 #[cfg(test)]
 mod tests;
@@ -36,6 +36,32 @@ impl ReplyParameters {
         }
     }
 
+    /// Creates a new `ReplyParameters` quoting the first occurrence of a substring in a message.
+    ///
+    /// See [`ReplyQuote::find`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to be replied to.
+    /// * `value` - The substring of the message text or caption to quote.
+    pub fn quote(message: &Message, value: &str) -> Result<Self, ReplyQuoteError> {
+        let text = message.get_text().ok_or(ReplyQuoteError::NoText)?;
+        Ok(Self::new(message.id).with_quote(ReplyQuote::find(text, value)?))
+    }
+
+    /// Creates a new `ReplyParameters` quoting a range of a message.
+    ///
+    /// See [`ReplyQuote::from_range`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to be replied to.
+    /// * `range` - The range of the message text or caption to quote in bytes.
+    pub fn quote_range(message: &Message, range: Range<usize>) -> Result<Self, ReplyQuoteError> {
+        let text = message.get_text().ok_or(ReplyQuoteError::NoText)?;
+        Ok(Self::new(message.id).with_quote(ReplyQuote::from_range(text, range)?))
+    }
+
     /// Sets a new value for an `allow_sending_without_reply` flag.
     ///
     /// # Arguments
@@ -95,6 +121,9 @@ pub struct ReplyQuote {
 }
 
 impl ReplyQuote {
+    /// Maximum length of a quote in UTF-16 code units.
+    pub const MAX_LEN: usize = 1024;
+
     /// Creates a new `ReplyQuote`.
     ///
     /// # Arguments
@@ -113,6 +142,64 @@ impl ReplyQuote {
         }
     }
 
+    /// Creates a new `ReplyQuote` from the first occurrence of a substring in a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the message to be replied to.
+    /// * `value` - The substring to quote.
+    pub fn find(text: &Text, value: &str) -> Result<Self, ReplyQuoteError> {
+        match text.data.find(value) {
+            Some(start) if !value.is_empty() => Self::from_range(text, start..start + value.len()),
+            _ => Err(ReplyQuoteError::NotFound),
+        }
+    }
+
+    /// Creates a new `ReplyQuote` from a range of a text.
+    ///
+    /// The position is converted to UTF-16 code units,
+    /// and entities intersecting the range are clipped to it.
+    /// Only entities allowed in quotes are kept:
+    /// bold, italic, underline, strikethrough, spoiler and custom emoji.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the message to be replied to.
+    /// * `range` - The range to quote in bytes; must lie on character boundaries.
+    pub fn from_range(text: &Text, range: Range<usize>) -> Result<Self, ReplyQuoteError> {
+        if range.is_empty() || text.data.get(range.clone()).is_none() {
+            return Err(ReplyQuoteError::InvalidRange(range));
+        }
+        let start = text.data[..range.start].encode_utf16().count();
+        let end = start + text.data[range].encode_utf16().count();
+        if end - start > Self::MAX_LEN {
+            return Err(ReplyQuoteError::TooLong(end - start));
+        }
+        let units: Vec<u16> = text.data.encode_utf16().collect();
+        let quote = text.slice(&units, start, end);
+        let entities: Vec<TextEntity> = quote
+            .entities
+            .into_iter()
+            .flatten()
+            .filter(|entity| {
+                matches!(
+                    entity,
+                    TextEntity::Bold(_)
+                        | TextEntity::CustomEmoji { .. }
+                        | TextEntity::Italic(_)
+                        | TextEntity::Spoiler(_)
+                        | TextEntity::Strikethrough(_)
+                        | TextEntity::Underline(_)
+                )
+            })
+            .collect();
+        let mut result = Self::new(start as Integer, quote.data);
+        if !entities.is_empty() {
+            result = result.with_entities(entities);
+        }
+        Ok(result)
+    }
+
     /// Sets a new list of entities.
     ///
     /// # Arguments
@@ -165,3 +252,34 @@ impl fmt::Display for ReplyParametersError {
         }
     }
 }
+
+/// Represents an error occurred when creating a [`ReplyQuote`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplyQuoteError {
+    /// A range is empty, out of bounds or not on character boundaries.
+    InvalidRange(Range<usize>),
+    /// A message has no text or caption.
+    NoText,
+    /// A substring is empty or not found in a text.
+    NotFound,
+    /// A quote is longer than [`ReplyQuote::MAX_LEN`]; contains the length in UTF-16 code units.
+    TooLong(usize),
+}
+
+impl Error for ReplyQuoteError {}
+
+impl fmt::Display for ReplyQuoteError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplyQuoteError::InvalidRange(range) => write!(out, "invalid quote range: {:?}", range),
+            ReplyQuoteError::NoText => write!(out, "message has no text to quote"),
+            ReplyQuoteError::NotFound => write!(out, "quote not found in message text"),
+            ReplyQuoteError::TooLong(len) => write!(
+                out,
+                "quote length {} exceeds the maximum of {} UTF-16 code units",
+                len,
+                ReplyQuote::MAX_LEN
+            ),
+        }
+    }
+}
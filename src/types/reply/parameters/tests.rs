@@ -1,4 +1,17 @@
-use crate::types::{tests::assert_json_eq, ParseMode, ReplyParameters, ReplyQuote, TextEntity};
+use crate::types::{
+    tests::assert_json_eq,
+    Message,
+    MessageData,
+    ParseMode,
+    ReplyParameters,
+    ReplyQuote,
+    ReplyQuoteError,
+    SupergroupChat,
+    Text,
+    TextEntities,
+    TextEntity,
+    User,
+};
 
 #[test]
 fn reply_parameters() {
@@ -38,3 +51,77 @@ fn reply_parameters() {
         }),
     );
 }
+
+fn create_text() -> Text {
+    Text::from("😀 hello world, hello").with_entities(TextEntities::from_iter([
+        TextEntity::bold(3..8),
+        TextEntity::text_link(9..14, "https://example.com"),
+        TextEntity::italic(0..21),
+    ]))
+}
+
+#[test]
+fn reply_quote_find() {
+    let text = create_text();
+    assert_eq!(
+        ReplyQuote::find(&text, "hello world").unwrap(),
+        ReplyQuote::new(3, "hello world").with_entities([TextEntity::bold(0..5), TextEntity::italic(0..11)])
+    );
+    assert_eq!(
+        ReplyQuote::find(&text, "world").unwrap(),
+        ReplyQuote::new(9, "world").with_entities([TextEntity::italic(0..5)])
+    );
+    assert_eq!(
+        ReplyQuote::find(&Text::from("test"), "es").unwrap(),
+        ReplyQuote::new(1, "es")
+    );
+    for value in ["missing", ""] {
+        assert_eq!(ReplyQuote::find(&text, value), Err(ReplyQuoteError::NotFound));
+    }
+}
+
+#[test]
+fn reply_quote_from_range() {
+    let text = create_text();
+    assert_eq!(
+        ReplyQuote::from_range(&text, 18..23).unwrap(),
+        ReplyQuote::new(16, "hello").with_entities([TextEntity::italic(0..5)])
+    );
+    for range in [1..3, 5..5, 0..100] {
+        assert_eq!(
+            ReplyQuote::from_range(&text, range.clone()),
+            Err(ReplyQuoteError::InvalidRange(range))
+        );
+    }
+    let text = Text::from("a".repeat(ReplyQuote::MAX_LEN + 1));
+    assert!(ReplyQuote::from_range(&text, 0..ReplyQuote::MAX_LEN).is_ok());
+    assert_eq!(
+        ReplyQuote::from_range(&text, 0..ReplyQuote::MAX_LEN + 1),
+        Err(ReplyQuoteError::TooLong(ReplyQuote::MAX_LEN + 1))
+    );
+}
+
+#[test]
+fn reply_parameters_quote() {
+    let create_message = |data| Message::new(1, 0, SupergroupChat::new(1, "test"), data, User::new(1, "test", false));
+    let message = create_message(MessageData::Text(create_text()));
+    assert_eq!(
+        ReplyParameters::quote(&message, "world").unwrap(),
+        ReplyParameters::new(1).with_quote(ReplyQuote::new(9, "world").with_entities([TextEntity::italic(0..5)]))
+    );
+    assert_eq!(
+        ReplyParameters::quote_range(&message, 5..10).unwrap(),
+        ReplyParameters::new(1)
+            .with_quote(ReplyQuote::new(3, "hello").with_entities([TextEntity::bold(0..5), TextEntity::italic(0..5)]))
+    );
+    assert_eq!(
+        ReplyParameters::quote(&message, "missing"),
+        Err(ReplyQuoteError::NotFound)
+    );
+    let message = create_message(MessageData::Unknown(serde_json::json!({})));
+    assert_eq!(ReplyParameters::quote(&message, "test"), Err(ReplyQuoteError::NoText));
+    assert_eq!(
+        ReplyParameters::quote_range(&message, 0..1),
+        Err(ReplyQuoteError::NoText)
+    );
+}
@@ -0,0 +1,184 @@
+use crate::types::{Text, TextEntity, TextEntityPosition, User};
+
+#[cfg(test)]
+mod tests;
+
+/// Builds a [`Text`] with entities.
+///
+/// Offsets and lengths of entities are calculated in UTF-16 code units,
+/// so the text can be sent without a parse mode.
+///
+/// Methods applying an entity accept anything convertible into a [`Text`],
+/// including another `TextBuilder`, which allows to nest entities.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::types::{TextBuilder, TextEntity};
+///
+/// let text = TextBuilder::default()
+///     .plain("🦀 ")
+///     .bold(TextBuilder::default().plain("Hello, ").italic("world"))
+///     .plain("!")
+///     .build();
+/// assert_eq!(text.data, "🦀 Hello, world!");
+/// let entities: Vec<TextEntity> = text.entities.unwrap().into();
+/// assert_eq!(entities, vec![TextEntity::bold(3..15), TextEntity::italic(10..15)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TextBuilder {
+    data: String,
+    entities: Vec<TextEntity>,
+    len: u32,
+}
+
+macro_rules! text_builder_entity {
+    ($($method_name:ident => $factory: ident),*) => {
+        $(
+            #[doc = concat!("Appends a text with the `", stringify!($factory), "` entity.")]
+            ///
+            /// # Arguments
+            ///
+            /// * `value` - The text; may contain nested entities.
+            pub fn $method_name<T: Into<Text>>(self, value: T) -> Self {
+                self.wrap(value, TextEntity::$factory)
+            }
+        )*
+    };
+}
+
+impl TextBuilder {
+    text_builder_entity!(
+        blockquote => blockquote,
+        bold => bold,
+        expandable_blockquote => expandable_blockquote,
+        italic => italic,
+        spoiler => spoiler,
+        strikethrough => strikethrough,
+        underline => underline
+    );
+
+    /// Appends a text.
+    ///
+    /// Entities of the text are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text.
+    pub fn plain<T: Into<Text>>(mut self, value: T) -> Self {
+        self.push(value.into());
+        self
+    }
+
+    /// Appends a monospace string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string.
+    pub fn code<T: Into<String>>(self, value: T) -> Self {
+        self.wrap(value.into(), TextEntity::code)
+    }
+
+    /// Appends a monospace block.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The code.
+    /// * `language` - The programming language of the code.
+    pub fn pre<T: Into<String>>(self, value: T, language: Option<&str>) -> Self {
+        self.wrap(value.into(), |position| TextEntity::pre(position, language))
+    }
+
+    /// Appends a clickable text URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text; may contain nested entities.
+    /// * `url` - URL that will be opened after user taps on the text.
+    pub fn text_link<T: Into<Text>, U: Into<String>>(self, value: T, url: U) -> Self {
+        let url = url.into();
+        self.wrap(value, |position| TextEntity::text_link(position, url))
+    }
+
+    /// Appends a mention of a user without a username.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text; may contain nested entities.
+    /// * `user` - The user to be mentioned.
+    pub fn text_mention<T: Into<Text>>(self, value: T, user: User) -> Self {
+        self.wrap(value, |position| TextEntity::text_mention(position, user))
+    }
+
+    /// Appends a custom emoji.
+    ///
+    /// # Arguments
+    ///
+    /// * `emoji` - The alternative emoji shown when custom emoji are not available.
+    /// * `custom_emoji_id` - Unique identifier of the custom emoji.
+    pub fn custom_emoji<T: Into<String>, I: Into<String>>(self, emoji: T, custom_emoji_id: I) -> Self {
+        let custom_emoji_id = custom_emoji_id.into();
+        self.wrap(emoji.into(), |position| {
+            TextEntity::custom_emoji(position, custom_emoji_id)
+        })
+    }
+
+    /// Indicates whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the length of the text in UTF-16 code units.
+    pub fn len_utf16(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns the text with entities.
+    ///
+    /// Entities are sorted by offset, an outer entity goes before the nested ones.
+    pub fn build(mut self) -> Text {
+        self.entities.sort_by(|a, b| {
+            let (a, b) = (a.get_position(), b.get_position());
+            a.offset.cmp(&b.offset).then(b.length.cmp(&a.length))
+        });
+        Text {
+            data: self.data,
+            entities: if self.entities.is_empty() {
+                None
+            } else {
+                Some(self.entities.into_iter().collect())
+            },
+        }
+    }
+
+    fn push(&mut self, value: Text) {
+        let offset = self.len;
+        self.len += value.data.encode_utf16().count() as u32;
+        self.data.push_str(&value.data);
+        self.entities
+            .extend(value.entities.into_iter().flatten().map(|mut entity| {
+                entity.get_position_mut().offset += offset;
+                entity
+            }));
+    }
+
+    fn wrap<T, F>(mut self, value: T, entity: F) -> Self
+    where
+        T: Into<Text>,
+        F: FnOnce(TextEntityPosition) -> TextEntity,
+    {
+        let offset = self.len;
+        let index = self.entities.len();
+        self.push(value.into());
+        if self.len > offset {
+            self.entities.insert(index, entity((offset..self.len).into()));
+        }
+        self
+    }
+}
+
+impl From<TextBuilder> for Text {
+    fn from(value: TextBuilder) -> Self {
+        value.build()
+    }
+}
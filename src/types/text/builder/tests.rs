@@ -0,0 +1,81 @@
+use crate::types::{TextBuilder, TextEntity, User};
+
+#[test]
+fn text_builder() {
+    let user = User::new(1, "test", false);
+    let text = TextBuilder::default()
+        .plain("p ")
+        .bold("b")
+        .italic("i")
+        .underline("u")
+        .strikethrough("s")
+        .spoiler("sp")
+        .code("c")
+        .pre("fn main() {}", Some("rust"))
+        .pre("pre", None)
+        .text_link("l", "https://example.com")
+        .text_mention("tm", user.clone())
+        .custom_emoji("👍", "emoji-id")
+        .blockquote("bq")
+        .expandable_blockquote("ebq")
+        .build();
+    assert_eq!(text.data, "p biusspcfn main() {}preltm👍bqebq");
+    let entities: Vec<TextEntity> = text.entities.unwrap().into();
+    assert_eq!(
+        entities,
+        vec![
+            TextEntity::bold(2..3),
+            TextEntity::italic(3..4),
+            TextEntity::underline(4..5),
+            TextEntity::strikethrough(5..6),
+            TextEntity::spoiler(6..8),
+            TextEntity::code(8..9),
+            TextEntity::pre(9..21, Some("rust")),
+            TextEntity::pre::<_, String>(21..24, None),
+            TextEntity::text_link(24..25, "https://example.com"),
+            TextEntity::text_mention(25..27, user),
+            TextEntity::custom_emoji(27..29, "emoji-id"),
+            TextEntity::blockquote(29..31),
+            TextEntity::expandable_blockquote(31..34),
+        ]
+    );
+}
+
+#[test]
+fn text_builder_nested() {
+    let text = TextBuilder::default()
+        .plain("𝕋 ")
+        .bold(
+            TextBuilder::default()
+                .plain("ab ")
+                .italic(TextBuilder::default().underline("ё").plain("🦀"))
+                .plain(" "),
+        )
+        .text_link(TextBuilder::default().bold("x"), "https://example.com")
+        .build();
+    assert_eq!(text.data, "𝕋 ab ё🦀 x");
+    let entities: Vec<TextEntity> = text.entities.unwrap().into();
+    assert_eq!(
+        entities,
+        vec![
+            TextEntity::bold(3..10),
+            TextEntity::italic(6..9),
+            TextEntity::underline(6..7),
+            TextEntity::text_link(10..11, "https://example.com"),
+            TextEntity::bold(10..11),
+        ]
+    );
+}
+
+#[test]
+fn text_builder_empty() {
+    let builder = TextBuilder::default();
+    assert!(builder.is_empty());
+    let text = builder.plain("").bold("").build();
+    assert_eq!(text.data, "");
+    assert!(text.entities.is_none());
+
+    let builder = TextBuilder::default().plain("🦀").bold("a");
+    assert!(!builder.is_empty());
+    assert_eq!(builder.len_utf16(), 3);
+}
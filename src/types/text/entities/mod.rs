@@ -253,6 +253,57 @@ impl TextEntity {
             user,
         }
     }
+
+    /// Returns the position of the entity in UTF-16 code units.
+    pub fn get_position(&self) -> TextEntityPosition {
+        use self::TextEntity::*;
+        match self {
+            Blockquote(position)
+            | Bold(position)
+            | BotCommand(position)
+            | Cashtag(position)
+            | Code(position)
+            | Email(position)
+            | ExpandableBlockquote(position)
+            | Hashtag(position)
+            | Italic(position)
+            | Mention(position)
+            | PhoneNumber(position)
+            | Spoiler(position)
+            | Strikethrough(position)
+            | Underline(position)
+            | Url(position) => *position,
+            CustomEmoji { position, .. }
+            | Pre { position, .. }
+            | TextLink { position, .. }
+            | TextMention { position, .. } => *position,
+        }
+    }
+
+    pub(crate) fn get_position_mut(&mut self) -> &mut TextEntityPosition {
+        use self::TextEntity::*;
+        match self {
+            Blockquote(position)
+            | Bold(position)
+            | BotCommand(position)
+            | Cashtag(position)
+            | Code(position)
+            | Email(position)
+            | ExpandableBlockquote(position)
+            | Hashtag(position)
+            | Italic(position)
+            | Mention(position)
+            | PhoneNumber(position)
+            | Spoiler(position)
+            | Strikethrough(position)
+            | Underline(position)
+            | Url(position) => position,
+            CustomEmoji { position, .. }
+            | Pre { position, .. }
+            | TextLink { position, .. }
+            | TextMention { position, .. } => position,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -0,0 +1,150 @@
+use crate::types::{Text, TextBuilder, User};
+
+#[cfg(test)]
+mod tests;
+
+macro_rules! text_fragment {
+    ($($method_name:ident),*) => {
+        $(
+            #[doc = concat!("Creates a text fragment with the `", stringify!($method_name), "` entity.")]
+            ///
+            /// # Arguments
+            ///
+            /// * `value` - The text; may contain nested entities.
+            pub fn $method_name<T: Into<Text>>(value: T) -> Self {
+                TextBuilder::default().$method_name(value).build()
+            }
+        )*
+    };
+}
+
+impl Text {
+    text_fragment!(
+        blockquote,
+        bold,
+        expandable_blockquote,
+        italic,
+        spoiler,
+        strikethrough,
+        underline
+    );
+
+    /// Creates a text fragment with the `code` entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string.
+    pub fn code<T: Into<String>>(value: T) -> Self {
+        TextBuilder::default().code(value).build()
+    }
+
+    /// Creates a text fragment with the `pre` entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The code.
+    /// * `language` - The programming language of the code.
+    pub fn pre<T: Into<String>>(value: T, language: Option<&str>) -> Self {
+        TextBuilder::default().pre(value, language).build()
+    }
+
+    /// Creates a text fragment with the `text_link` entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text; may contain nested entities.
+    /// * `url` - URL that will be opened after user taps on the text.
+    pub fn text_link<T: Into<Text>, U: Into<String>>(value: T, url: U) -> Self {
+        TextBuilder::default().text_link(value, url).build()
+    }
+
+    /// Creates a text fragment with the `text_mention` entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text; may contain nested entities.
+    /// * `user` - The user to be mentioned.
+    pub fn text_mention<T: Into<Text>>(value: T, user: User) -> Self {
+        TextBuilder::default().text_mention(value, user).build()
+    }
+
+    /// Creates a text fragment with the `custom_emoji` entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `emoji` - The alternative emoji shown when custom emoji are not available.
+    /// * `custom_emoji_id` - Unique identifier of the custom emoji.
+    pub fn custom_emoji<T: Into<String>, I: Into<String>>(emoji: T, custom_emoji_id: I) -> Self {
+        TextBuilder::default().custom_emoji(emoji, custom_emoji_id).build()
+    }
+}
+
+/// Concatenates fragments into a [`Text`](crate::types::Text).
+///
+/// Each fragment is anything convertible into a `Text`:
+/// a string, a [`TextBuilder`](crate::types::TextBuilder) or a fragment created
+/// using constructors such as [`Text::bold`](crate::types::Text::bold) and [`Text::code`](crate::types::Text::code).
+/// Strings are never parsed as markup.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{format_text, types::{Text, TextEntity}};
+///
+/// let text = format_text!("Hello, ", Text::bold("<John>"), "!");
+/// assert_eq!(text.data, "Hello, <John>!");
+/// let entities: Vec<TextEntity> = text.entities.unwrap().into();
+/// assert_eq!(entities, vec![TextEntity::bold(7..13)]);
+/// ```
+#[macro_export]
+macro_rules! format_text {
+    ($($fragment:expr),* $(,)?) => {
+        $crate::types::TextBuilder::default()$(.plain($fragment))*.build()
+    };
+}
+
+/// Concatenates fragments and renders them as HTML.
+///
+/// See [`format_text!`] for details about fragments;
+/// the result can be sent using [`ParseMode::Html`](crate::types::ParseMode::Html).
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{format_html, types::Text};
+///
+/// let name = "<John>";
+/// let url = "https://example.com/?a=1&b=\"2\"";
+/// assert_eq!(
+///     format_html!("Hello, ", Text::bold(name), "! ", Text::text_link("Docs", url)),
+///     "Hello, <b>&lt;John&gt;</b>! <a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">Docs</a>"
+/// );
+/// ```
+#[macro_export]
+macro_rules! format_html {
+    ($($fragment:expr),* $(,)?) => {
+        $crate::format_text!($($fragment),*).to_html()
+    };
+}
+
+/// Concatenates fragments and renders them as MarkdownV2.
+///
+/// See [`format_text!`] for details about fragments;
+/// the result can be sent using [`ParseMode::MarkdownV2`](crate::types::ParseMode::MarkdownV2).
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{format_markdown_v2, types::Text};
+///
+/// assert_eq!(
+///     format_markdown_v2!("1 + 1 = ", Text::code("`2`"), "."),
+///     r"1 \+ 1 \= `\`2\``\."
+/// );
+/// ```
+#[macro_export]
+macro_rules! format_markdown_v2 {
+    ($($fragment:expr),* $(,)?) => {
+        $crate::format_text!($($fragment),*).to_markdown_v2()
+    };
+}
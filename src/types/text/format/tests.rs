@@ -0,0 +1,80 @@
+use crate::types::{Text, TextBuilder, TextEntity, User};
+
+#[test]
+fn text_fragments() {
+    let user = User::new(1, "John", false);
+    for (fragment, entity) in [
+        (Text::blockquote("test"), TextEntity::blockquote(0..4)),
+        (Text::bold("test"), TextEntity::bold(0..4)),
+        (
+            Text::expandable_blockquote("test"),
+            TextEntity::expandable_blockquote(0..4),
+        ),
+        (Text::italic("test"), TextEntity::italic(0..4)),
+        (Text::spoiler("test"), TextEntity::spoiler(0..4)),
+        (Text::strikethrough("test"), TextEntity::strikethrough(0..4)),
+        (Text::underline("test"), TextEntity::underline(0..4)),
+        (Text::code("test"), TextEntity::code(0..4)),
+        (Text::pre("test", Some("rust")), TextEntity::pre(0..4, Some("rust"))),
+        (
+            Text::text_link("test", "https://example.com"),
+            TextEntity::text_link(0..4, "https://example.com"),
+        ),
+        (
+            Text::text_mention("test", user.clone()),
+            TextEntity::text_mention(0..4, user.clone()),
+        ),
+        (Text::custom_emoji("😀", "1"), TextEntity::custom_emoji(0..2, "1")),
+    ] {
+        let entities: Vec<TextEntity> = fragment.entities.unwrap().into();
+        assert_eq!(entities, vec![entity]);
+    }
+    assert!(Text::bold("").entities.is_none());
+}
+
+#[test]
+fn format_text() {
+    let text = crate::format_text!(
+        "😀 ",
+        Text::bold(Text::italic("a")),
+        String::from(" "),
+        TextBuilder::default().underline("b"),
+    );
+    assert_eq!(text.data, "😀 a b");
+    let entities: Vec<TextEntity> = text.entities.unwrap().into();
+    assert_eq!(
+        entities,
+        vec![
+            TextEntity::bold(3..4),
+            TextEntity::italic(3..4),
+            TextEntity::underline(5..6)
+        ]
+    );
+    assert_eq!(crate::format_text!(), Text::from(""));
+}
+
+#[test]
+fn format_markup() {
+    let value = r#"<a href="x">*_[x](y)_*</a> & \"#;
+    assert_eq!(
+        crate::format_html!(
+            Text::bold(value),
+            " ",
+            Text::text_link(value, "https://t.me/?a=\"b\"&c")
+        ),
+        concat!(
+            r#"<b>&lt;a href="x"&gt;*_[x](y)_*&lt;/a&gt; &amp; \</b> "#,
+            r#"<a href="https://t.me/?a=&quot;b&quot;&amp;c">&lt;a href="x"&gt;*_[x](y)_*&lt;/a&gt; &amp; \</a>"#,
+        )
+    );
+    assert_eq!(
+        crate::format_markdown_v2!(
+            Text::bold("a_b"),
+            " ",
+            Text::code("`\\`"),
+            " ",
+            Text::text_link("c)", "https://t.me/(x)\\"),
+        ),
+        r"*a\_b* `\`\\\`` [c\)](https://t.me/(x\)\\)"
+    );
+}
@@ -1,13 +1,19 @@
-use std::str::EncodeUtf16;
+use std::ops::Range;
 
 use serde::{Deserialize, Serialize};
 
-pub use self::entities::*;
+pub use self::{builder::*, entities::*, parse::TextParseError, template::*};
 
 #[cfg(test)]
 mod tests;
 
+mod builder;
 mod entities;
+mod format;
+mod parse;
+mod render;
+mod split;
+mod template;
 
 /// Represents a text with entities.
 #[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
@@ -31,28 +37,115 @@ impl Text {
 
     /// Returns the list of bot commands found in text.
     pub fn get_bot_commands(&self) -> Option<Vec<TextEntityBotCommand>> {
+        let commands: Vec<TextEntityBotCommand> = self
+            .entity_contents()
+            .filter(|(entity, _, _)| matches!(entity, TextEntity::BotCommand(_)))
+            .map(|(_, content, _)| {
+                let (command, bot_name) = match content.split_once('@') {
+                    Some((command, bot_name)) => (command, Some(String::from(bot_name))),
+                    None => (content, None),
+                };
+                TextEntityBotCommand {
+                    command: String::from(command),
+                    bot_name,
+                }
+            })
+            .collect();
+        if commands.is_empty() {
+            None
+        } else {
+            Some(commands)
+        }
+    }
+
+    /// Returns an iterator over entities and their content.
+    ///
+    /// Each item contains an entity, the content of the entity
+    /// and the range of the content in bytes of [`Self::data`].
+    ///
+    /// Entities pointing outside of the text or inside a character are skipped.
+    pub fn entity_contents(&self) -> impl Iterator<Item = (&TextEntity, &str, Range<usize>)> {
+        let mut offsets = Vec::with_capacity(self.data.len() + 1);
+        for (idx, c) in self.data.char_indices() {
+            offsets.push(Some(idx));
+            if c.len_utf16() == 2 {
+                offsets.push(None);
+            }
+        }
+        offsets.push(Some(self.data.len()));
+        self.entities.iter().flatten().filter_map(move |entity| {
+            let position = entity.get_position();
+            let start = position.offset as usize;
+            let end = start + position.length as usize;
+            let range = offsets.get(start).copied().flatten()?..offsets.get(end).copied().flatten()?;
+            Some((entity, &self.data[range.clone()], range))
+        })
+    }
+
+    /// Returns the list of URLs found in text.
+    ///
+    /// See [`Self::text_links`] for URLs of text links.
+    pub fn urls(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::Url(_)))
+    }
+
+    /// Returns the list of mentions found in text, including the leading `@`.
+    pub fn mentions(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::Mention(_)))
+    }
+
+    /// Returns the list of hashtags found in text, including the leading `#`.
+    pub fn hashtags(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::Hashtag(_)))
+    }
+
+    /// Returns the list of cashtags found in text, including the leading `$`.
+    pub fn cashtags(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::Cashtag(_)))
+    }
+
+    /// Returns the list of email addresses found in text.
+    pub fn emails(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::Email(_)))
+    }
+
+    /// Returns the list of phone numbers found in text.
+    pub fn phone_numbers(&self) -> Vec<&str> {
+        self.filter_contents(|entity| matches!(entity, TextEntity::PhoneNumber(_)))
+    }
+
+    /// Returns the list of URLs of text links.
+    pub fn text_links(&self) -> Vec<&str> {
+        self.entities
+            .iter()
+            .flatten()
+            .filter_map(|entity| match entity {
+                TextEntity::TextLink { url, .. } => Some(url.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the list of custom emoji identifiers.
+    pub fn custom_emoji_ids(&self) -> Vec<&str> {
         self.entities
-            .as_ref()
-            .map(|entities| {
-                let repr = TextRepr::from(self);
-                entities
-                    .into_iter()
-                    .filter_map(|entity| {
-                        if let TextEntity::BotCommand(position) = entity {
-                            let entity_data = repr.get_entity_content(*position);
-                            let parts = entity_data.as_str().splitn(2, '@').collect::<Vec<&str>>();
-                            let len = parts.len();
-                            assert!(len >= 1);
-                            let command = parts[0].to_string();
-                            let bot_name = if len == 2 { Some(parts[1].to_string()) } else { None };
-                            Some(TextEntityBotCommand { command, bot_name })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<TextEntityBotCommand>>()
+            .iter()
+            .flatten()
+            .filter_map(|entity| match entity {
+                TextEntity::CustomEmoji { custom_emoji_id, .. } => Some(custom_emoji_id.as_str()),
+                _ => None,
             })
-            .filter(|entities| !entities.is_empty())
+            .collect()
+    }
+
+    fn filter_contents<P>(&self, predicate: P) -> Vec<&str>
+    where
+        P: Fn(&TextEntity) -> bool,
+    {
+        self.entity_contents()
+            .filter(|(entity, _, _)| predicate(entity))
+            .map(|(_, content, _)| content)
+            .collect()
     }
 }
 
@@ -91,22 +184,3 @@ impl<'a> From<&'a str> for Text {
         }
     }
 }
-
-struct TextRepr<'a> {
-    iter: EncodeUtf16<'a>,
-}
-
-impl<'a> From<&'a Text> for TextRepr<'a> {
-    fn from(text: &'a Text) -> Self {
-        Self {
-            iter: text.data.encode_utf16(),
-        }
-    }
-}
-
-impl TextRepr<'_> {
-    fn get_entity_content(&self, position: TextEntityPosition) -> String {
-        let (offset, length) = (position.offset as usize, position.length as usize);
-        String::from_utf16_lossy(&self.iter.clone().skip(offset).take(length).collect::<Vec<u16>>())
-    }
-}
@@ -0,0 +1,218 @@
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+
+use super::TextOutput;
+use crate::types::{Text, TextEntity};
+
+enum EntityKind {
+    Blockquote,
+    Bold,
+    Code,
+    Italic,
+    Pre(Option<String>),
+    Strikethrough,
+    TextLink(String),
+}
+
+struct OpenEntity {
+    kind: EntityKind,
+    order: usize,
+    start: u32,
+}
+
+struct Converter {
+    output: TextOutput,
+    stack: Vec<Option<OpenEntity>>,
+    order: usize,
+    line_breaks: usize,
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    block_text: Option<String>,
+    is_table_cell_first: bool,
+}
+
+pub(super) fn parse(input: &str) -> Text {
+    let mut converter = Converter {
+        output: TextOutput::default(),
+        stack: Vec::new(),
+        order: 0,
+        line_breaks: 0,
+        lists: Vec::new(),
+        quote_depth: 0,
+        block_text: None,
+        is_table_cell_first: false,
+    };
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+    for event in Parser::new_ext(input, options) {
+        converter.handle(event);
+    }
+    converter.output.finish()
+}
+
+impl Converter {
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(value) | Event::Html(value) | Event::InlineHtml(value) => match self.block_text {
+                Some(ref mut block_text) => block_text.push_str(&value),
+                None => self.push_str(&value),
+            },
+            Event::Code(value) => {
+                self.open(Some(EntityKind::Code));
+                self.push_str(&value);
+                self.close();
+            }
+            Event::SoftBreak | Event::HardBreak => self.push_str("\n"),
+            Event::Rule => {
+                self.push_str("———");
+                self.break_lines(2);
+            }
+            Event::TaskListMarker(is_checked) => self.push_str(if is_checked { "☑ " } else { "☐ " }),
+            Event::FootnoteReference(value) | Event::InlineMath(value) | Event::DisplayMath(value) => {
+                self.push_str(&value)
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        if matches!(tag, Tag::Item) && !self.output.data.ends_with('\n') {
+            self.break_lines(1);
+        }
+        self.flush_line_breaks();
+        let kind = match tag {
+            Tag::Heading { .. } | Tag::Strong => Some(EntityKind::Bold),
+            Tag::Emphasis => Some(EntityKind::Italic),
+            Tag::Strikethrough => Some(EntityKind::Strikethrough),
+            Tag::BlockQuote(_) => {
+                self.quote_depth += 1;
+                // Telegram does not support nested blockquotes
+                (self.quote_depth == 1).then_some(EntityKind::Blockquote)
+            }
+            Tag::CodeBlock(kind) => {
+                self.block_text = Some(String::new());
+                Some(EntityKind::Pre(match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(String::from),
+                    CodeBlockKind::Indented => None,
+                }))
+            }
+            Tag::HtmlBlock => {
+                self.block_text = Some(String::new());
+                None
+            }
+            Tag::Link {
+                link_type, dest_url, ..
+            } => {
+                // Telegram detects such links automatically,
+                // and a relative URL can not be used in a text link
+                let is_auto = matches!(link_type, LinkType::Autolink | LinkType::Email);
+                (!is_auto && dest_url.contains(':')).then(|| EntityKind::TextLink(dest_url.into_string()))
+            }
+            Tag::List(number) => {
+                self.lists.push(number);
+                None
+            }
+            Tag::Item => {
+                let depth = self.lists.len().saturating_sub(1);
+                self.push_str(&"  ".repeat(depth));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => String::from("• "),
+                };
+                self.push_str(&marker);
+                None
+            }
+            Tag::TableHead => {
+                self.is_table_cell_first = true;
+                Some(EntityKind::Bold)
+            }
+            Tag::TableRow => {
+                self.is_table_cell_first = true;
+                None
+            }
+            Tag::TableCell => {
+                if !self.is_table_cell_first {
+                    self.push_str(" | ");
+                }
+                self.is_table_cell_first = false;
+                None
+            }
+            _ => None,
+        };
+        self.open(kind);
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        if let Some(block_text) = self.block_text.take() {
+            let block_text = block_text.strip_suffix('\n').unwrap_or(&block_text);
+            self.push_str(block_text);
+        }
+        self.close();
+        match tag {
+            TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::CodeBlock
+            | TagEnd::HtmlBlock
+            | TagEnd::Table
+            | TagEnd::FootnoteDefinition => self.break_lines(2),
+            TagEnd::BlockQuote(_) => {
+                self.quote_depth -= 1;
+                self.break_lines(2);
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.break_lines(if self.lists.is_empty() { 2 } else { 1 });
+            }
+            TagEnd::Item | TagEnd::TableHead | TagEnd::TableRow => self.break_lines(1),
+            _ => {}
+        }
+    }
+
+    fn open(&mut self, kind: Option<EntityKind>) {
+        self.order += 1;
+        self.stack.push(kind.map(|kind| OpenEntity {
+            kind,
+            order: self.order,
+            start: self.output.len,
+        }));
+    }
+
+    fn close(&mut self) {
+        let entity = match self.stack.pop() {
+            Some(Some(entity)) => entity,
+            _ => return,
+        };
+        self.output
+            .push_entity(entity.order, entity.start, |position| match entity.kind {
+                EntityKind::Blockquote => TextEntity::Blockquote(position),
+                EntityKind::Bold => TextEntity::Bold(position),
+                EntityKind::Code => TextEntity::Code(position),
+                EntityKind::Italic => TextEntity::Italic(position),
+                EntityKind::Pre(language) => TextEntity::Pre { position, language },
+                EntityKind::Strikethrough => TextEntity::Strikethrough(position),
+                EntityKind::TextLink(url) => TextEntity::text_link(position, url),
+            });
+    }
+
+    fn break_lines(&mut self, count: usize) {
+        self.line_breaks = self.line_breaks.max(count);
+    }
+
+    fn flush_line_breaks(&mut self) {
+        if !self.output.data.is_empty() {
+            for _ in 0..self.line_breaks {
+                self.output.push('\n');
+            }
+        }
+        self.line_breaks = 0;
+    }
+
+    fn push_str(&mut self, value: &str) {
+        self.flush_line_breaks();
+        for c in value.chars() {
+            self.output.push(c);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::{TextOutput, TextParseError};
+use crate::types::{Text, TextEntity};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TagKind {
+    Blockquote,
+    Bold,
+    Code,
+    CustomEmoji,
+    Italic,
+    Link,
+    Pre,
+    Spoiler,
+    Strikethrough,
+    Underline,
+}
+
+struct OpenTag {
+    name: String,
+    kind: TagKind,
+    order: usize,
+    start: u32,
+    offset: usize,
+    value: Option<String>,
+    is_expandable: bool,
+    is_merged: bool,
+}
+
+pub(super) fn parse(input: &str) -> Result<Text, TextParseError> {
+    let mut output = TextOutput::default();
+    let mut stack: Vec<OpenTag> = Vec::new();
+    let mut order = 0;
+    let mut position = 0;
+    while let Some(c) = input[position..].chars().next() {
+        match c {
+            '<' => {
+                let end = match input[position..].find('>') {
+                    Some(end) => position + end,
+                    None => return Err(TextParseError::UnclosedTag { offset: position }),
+                };
+                let tag = &input[position + 1..end];
+                if let Some(name) = tag.strip_prefix('/') {
+                    close_tag(&mut output, &mut stack, name, position)?;
+                } else {
+                    let tag = open_tag(&mut stack, tag, position, output.len, order)?;
+                    stack.push(tag);
+                    order += 1;
+                }
+                position = end + 1;
+            }
+            '&' => match decode_entity(&input[position..]) {
+                Some((value, len)) => {
+                    output.push(value);
+                    position += len;
+                }
+                None => {
+                    output.push('&');
+                    position += 1;
+                }
+            },
+            _ => {
+                output.push(c);
+                position += c.len_utf8();
+            }
+        }
+    }
+    match stack.pop() {
+        Some(tag) => Err(TextParseError::UnclosedEntity {
+            name: tag.name,
+            offset: tag.offset,
+        }),
+        None => Ok(output.finish()),
+    }
+}
+
+fn open_tag(
+    stack: &mut [OpenTag],
+    tag: &str,
+    offset: usize,
+    start: u32,
+    order: usize,
+) -> Result<OpenTag, TextParseError> {
+    let tag = tag.trim_end_matches('/');
+    let name_len = tag.find(|c: char| c.is_ascii_whitespace()).unwrap_or(tag.len());
+    let name = tag[..name_len].to_ascii_lowercase();
+    let attributes = parse_attributes(&tag[name_len..]);
+    let get_attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.clone())
+    };
+    let unsupported = || TextParseError::UnsupportedTag {
+        tag: name.clone(),
+        offset,
+    };
+    let missing_attribute = |attribute| TextParseError::MissingAttribute {
+        tag: name.clone(),
+        attribute,
+        offset,
+    };
+    let mut value = None;
+    let mut is_expandable = false;
+    let mut is_merged = false;
+    let kind = match name.as_str() {
+        "b" | "strong" => TagKind::Bold,
+        "i" | "em" => TagKind::Italic,
+        "u" | "ins" => TagKind::Underline,
+        "s" | "strike" | "del" => TagKind::Strikethrough,
+        "tg-spoiler" => TagKind::Spoiler,
+        "span" => match get_attribute("class") {
+            Some(class) if class == "tg-spoiler" => TagKind::Spoiler,
+            _ => return Err(unsupported()),
+        },
+        "a" => {
+            value = Some(get_attribute("href").ok_or_else(|| missing_attribute("href"))?);
+            TagKind::Link
+        }
+        "tg-emoji" => {
+            value = Some(get_attribute("emoji-id").ok_or_else(|| missing_attribute("emoji-id"))?);
+            TagKind::CustomEmoji
+        }
+        "code" => {
+            let language = get_attribute("class").and_then(|x| x.strip_prefix("language-").map(String::from));
+            if let Some(pre) = stack.last_mut().filter(|x| x.kind == TagKind::Pre && x.start == start) {
+                if pre.value.is_none() {
+                    pre.value = language;
+                }
+                is_merged = true;
+            }
+            TagKind::Code
+        }
+        "pre" => TagKind::Pre,
+        "blockquote" => {
+            is_expandable = get_attribute("expandable").is_some();
+            TagKind::Blockquote
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(OpenTag {
+        name,
+        kind,
+        order,
+        start,
+        offset,
+        value,
+        is_expandable,
+        is_merged,
+    })
+}
+
+fn close_tag(
+    output: &mut TextOutput,
+    stack: &mut Vec<OpenTag>,
+    name: &str,
+    offset: usize,
+) -> Result<(), TextParseError> {
+    let name = name.trim().to_ascii_lowercase();
+    let tag = match stack.pop() {
+        Some(tag) if tag.name == name => tag,
+        _ => return Err(TextParseError::UnexpectedEndTag { tag: name, offset }),
+    };
+    if tag.is_merged {
+        return Ok(());
+    }
+    let value = tag.value.unwrap_or_default();
+    output.push_entity(tag.order, tag.start, |position| match tag.kind {
+        TagKind::Blockquote if tag.is_expandable => TextEntity::ExpandableBlockquote(position),
+        TagKind::Blockquote => TextEntity::Blockquote(position),
+        TagKind::Bold => TextEntity::Bold(position),
+        TagKind::Code => TextEntity::Code(position),
+        TagKind::CustomEmoji => TextEntity::custom_emoji(position, value),
+        TagKind::Italic => TextEntity::Italic(position),
+        TagKind::Link => TextEntity::text_link(position, value),
+        TagKind::Pre => TextEntity::Pre {
+            position,
+            language: Some(value).filter(|x| !x.is_empty()),
+        },
+        TagKind::Spoiler => TextEntity::Spoiler(position),
+        TagKind::Strikethrough => TextEntity::Strikethrough(position),
+        TagKind::Underline => TextEntity::Underline(position),
+    });
+    Ok(())
+}
+
+fn parse_attributes(input: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut input = input.trim_start();
+    while !input.is_empty() {
+        let name_len = input
+            .find(|c: char| c.is_ascii_whitespace() || c == '=')
+            .unwrap_or(input.len());
+        let name = String::from(&input[..name_len]);
+        input = input[name_len..].trim_start();
+        let mut value = String::new();
+        if let Some(rest) = input.strip_prefix('=') {
+            let rest = rest.trim_start();
+            let (raw_value, rest) = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let rest = &rest[1..];
+                    let end = rest.find(quote).unwrap_or(rest.len());
+                    (&rest[..end], rest.get(end + 1..).unwrap_or_default())
+                }
+                _ => {
+                    let end = rest.find(|c: char| c.is_ascii_whitespace()).unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+            value = decode_text(raw_value);
+            input = rest.trim_start();
+        }
+        if !name.is_empty() {
+            result.push((name, value));
+        }
+    }
+    result
+}
+
+fn decode_text(input: &str) -> String {
+    let mut result = String::new();
+    let mut position = 0;
+    while let Some(c) = input[position..].chars().next() {
+        let entity = if c == '&' {
+            decode_entity(&input[position..])
+        } else {
+            None
+        };
+        match entity {
+            Some((value, len)) => {
+                result.push(value);
+                position += len;
+            }
+            None => {
+                result.push(c);
+                position += c.len_utf8();
+            }
+        }
+    }
+    result
+}
+
+fn decode_entity(input: &str) -> Option<(char, usize)> {
+    if !input.starts_with('&') {
+        return None;
+    }
+    let end = input.find(';').filter(|x| (1..=10).contains(x))?;
+    let name = &input[1..end];
+    let value = match name {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(code) => u32::from_str_radix(code, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((value, end + 1))
+}
@@ -0,0 +1,312 @@
+use super::{TextOutput, TextParseError};
+use crate::types::{Text, TextEntity, ESCAPE_MARKDOWN_V2};
+
+const CUSTOM_EMOJI_URL: &str = "tg://emoji?id=";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EntityKind {
+    Bold,
+    CustomEmoji,
+    Italic,
+    Link,
+    Spoiler,
+    Strikethrough,
+    Underline,
+}
+
+impl EntityKind {
+    fn name(self) -> &'static str {
+        match self {
+            EntityKind::Bold => "bold",
+            EntityKind::CustomEmoji => "custom emoji",
+            EntityKind::Italic => "italic",
+            EntityKind::Link => "text link",
+            EntityKind::Spoiler => "spoiler",
+            EntityKind::Strikethrough => "strikethrough",
+            EntityKind::Underline => "underline",
+        }
+    }
+}
+
+struct OpenEntity {
+    kind: EntityKind,
+    order: usize,
+    start: u32,
+    offset: usize,
+}
+
+struct OpenQuote {
+    order: usize,
+    start: u32,
+    is_expandable: bool,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    output: TextOutput,
+    stack: Vec<OpenEntity>,
+    quote: Option<OpenQuote>,
+    order: usize,
+}
+
+pub(super) fn parse(input: &str) -> Result<Text, TextParseError> {
+    let mut parser = Parser {
+        input,
+        position: 0,
+        output: TextOutput::default(),
+        stack: Vec::new(),
+        quote: None,
+        order: 0,
+    };
+    parser.run()?;
+    Ok(parser.output.finish())
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn next_order(&mut self) -> usize {
+        self.order += 1;
+        self.order
+    }
+
+    fn run(&mut self) -> Result<(), TextParseError> {
+        self.open_quote();
+        while let Some(c) = self.rest().chars().next() {
+            let offset = self.position;
+            self.position += c.len_utf8();
+            match c {
+                '\\' => match self.rest().chars().next() {
+                    Some(next) => {
+                        self.position += next.len_utf8();
+                        self.output.push(next);
+                    }
+                    None => self.output.push(c),
+                },
+                '\n' => {
+                    self.close_quote_at_line_end();
+                    self.output.push(c);
+                    self.open_quote();
+                }
+                '_' => {
+                    if self.rest().starts_with('_') {
+                        self.position += 1;
+                        self.toggle(EntityKind::Underline, offset);
+                    } else {
+                        self.toggle(EntityKind::Italic, offset);
+                    }
+                }
+                '*' => self.toggle(EntityKind::Bold, offset),
+                '~' => self.toggle(EntityKind::Strikethrough, offset),
+                '|' => {
+                    if !self.rest().starts_with('|') {
+                        return Err(TextParseError::UnescapedCharacter { character: c, offset });
+                    }
+                    self.position += 1;
+                    if self.is_expandable_quote_end() {
+                        self.close_quote();
+                    } else {
+                        self.toggle(EntityKind::Spoiler, offset);
+                    }
+                }
+                '[' => self.open(EntityKind::Link, offset),
+                '!' if self.rest().starts_with('[') => {
+                    self.position += 1;
+                    self.open(EntityKind::CustomEmoji, offset);
+                }
+                ']' => self.close_link(offset)?,
+                '`' => self.parse_code(offset)?,
+                _ if ESCAPE_MARKDOWN_V2.contains(&c) => {
+                    return Err(TextParseError::UnescapedCharacter { character: c, offset });
+                }
+                _ => self.output.push(c),
+            }
+        }
+        if let Some(entity) = self.stack.pop() {
+            return Err(TextParseError::UnclosedEntity {
+                name: String::from(entity.kind.name()),
+                offset: entity.offset,
+            });
+        }
+        self.close_quote();
+        Ok(())
+    }
+
+    fn open(&mut self, kind: EntityKind, offset: usize) {
+        let order = self.next_order();
+        self.stack.push(OpenEntity {
+            kind,
+            order,
+            start: self.output.len,
+            offset,
+        });
+    }
+
+    fn toggle(&mut self, kind: EntityKind, offset: usize) {
+        match self.stack.last() {
+            Some(entity) if entity.kind == kind => {
+                let entity = self.stack.pop().unwrap();
+                self.output
+                    .push_entity(entity.order, entity.start, |position| match kind {
+                        EntityKind::Bold => TextEntity::Bold(position),
+                        EntityKind::Italic => TextEntity::Italic(position),
+                        EntityKind::Spoiler => TextEntity::Spoiler(position),
+                        EntityKind::Strikethrough => TextEntity::Strikethrough(position),
+                        _ => TextEntity::Underline(position),
+                    });
+            }
+            _ => self.open(kind, offset),
+        }
+    }
+
+    fn close_link(&mut self, offset: usize) -> Result<(), TextParseError> {
+        let entity = match self.stack.last() {
+            Some(entity) if matches!(entity.kind, EntityKind::Link | EntityKind::CustomEmoji) => {
+                self.stack.pop().unwrap()
+            }
+            _ => return Err(TextParseError::UnescapedCharacter { character: ']', offset }),
+        };
+        if !self.rest().starts_with('(') {
+            return Err(TextParseError::MissingUrl { offset });
+        }
+        self.position += 1;
+        let url_offset = self.position;
+        let mut url = String::new();
+        loop {
+            let c = match self.rest().chars().next() {
+                Some(c) => c,
+                None => return Err(TextParseError::MissingUrl { offset }),
+            };
+            self.position += c.len_utf8();
+            match c {
+                ')' => break,
+                '\\' => match self.rest().chars().next() {
+                    Some(next) => {
+                        self.position += next.len_utf8();
+                        url.push(next);
+                    }
+                    None => url.push(c),
+                },
+                _ => url.push(c),
+            }
+        }
+        if entity.kind == EntityKind::CustomEmoji {
+            let custom_emoji_id = match url.strip_prefix(CUSTOM_EMOJI_URL) {
+                Some(id) if !id.is_empty() && id.chars().all(|x| x.is_ascii_digit()) => String::from(id),
+                _ => return Err(TextParseError::InvalidCustomEmoji { offset: url_offset }),
+            };
+            self.output.push_entity(entity.order, entity.start, |position| {
+                TextEntity::custom_emoji(position, custom_emoji_id)
+            });
+        } else {
+            self.output.push_entity(entity.order, entity.start, |position| {
+                TextEntity::text_link(position, url)
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_code(&mut self, offset: usize) -> Result<(), TextParseError> {
+        let is_pre = self.rest().starts_with("``");
+        let mut language = None;
+        if is_pre {
+            self.position += 2;
+            let line_len = self.rest().find('\n').unwrap_or(0);
+            let line = &self.rest()[..line_len];
+            if !line.is_empty() && !line.contains(|c: char| c.is_whitespace() || c == '`') {
+                language = Some(String::from(line));
+                self.position += line_len;
+            }
+            if self.rest().starts_with('\n') {
+                self.position += 1;
+            }
+        }
+        let marker = if is_pre { "```" } else { "`" };
+        let order = self.next_order();
+        let start = self.output.len;
+        loop {
+            if let Some(rest) = self.rest().strip_prefix(marker) {
+                self.position = self.input.len() - rest.len();
+                break;
+            }
+            if is_pre && self.rest().starts_with("\n```") {
+                self.position += 1;
+                continue;
+            }
+            let c = match self.rest().chars().next() {
+                Some(c) => c,
+                None => {
+                    return Err(TextParseError::UnclosedEntity {
+                        name: String::from(if is_pre { "pre" } else { "code" }),
+                        offset,
+                    })
+                }
+            };
+            self.position += c.len_utf8();
+            match (c, self.rest().chars().next()) {
+                ('\\', Some(next @ ('`' | '\\'))) => {
+                    self.position += 1;
+                    self.output.push(next);
+                }
+                _ => self.output.push(c),
+            }
+        }
+        self.output.push_entity(order, start, |position| {
+            if is_pre {
+                TextEntity::Pre { position, language }
+            } else {
+                TextEntity::Code(position)
+            }
+        });
+        Ok(())
+    }
+
+    fn open_quote(&mut self) {
+        let (len, is_expandable) = if self.rest().starts_with("**>") {
+            (3, true)
+        } else if self.rest().starts_with('>') {
+            (1, false)
+        } else {
+            return;
+        };
+        self.position += len;
+        if self.quote.is_none() || is_expandable {
+            self.close_quote();
+            let order = self.next_order();
+            self.quote = Some(OpenQuote {
+                order,
+                start: self.output.len,
+                is_expandable,
+            });
+        }
+    }
+
+    fn close_quote_at_line_end(&mut self) {
+        let rest = self.rest();
+        if !rest.starts_with('>') || rest.starts_with("**>") {
+            self.close_quote();
+        }
+    }
+
+    fn is_expandable_quote_end(&self) -> bool {
+        let rest = self.rest();
+        self.quote.as_ref().is_some_and(|x| x.is_expandable)
+            && self.stack.last().is_none_or(|x| x.kind != EntityKind::Spoiler)
+            && (rest.is_empty() || rest.starts_with('\n'))
+    }
+
+    fn close_quote(&mut self) {
+        if let Some(quote) = self.quote.take() {
+            self.output.push_entity(quote.order, quote.start, |position| {
+                if quote.is_expandable {
+                    TextEntity::ExpandableBlockquote(position)
+                } else {
+                    TextEntity::Blockquote(position)
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::{cmp::Reverse, error::Error, fmt};
+
+use crate::types::{Text, TextEntity, TextEntityPosition};
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "commonmark")]
+mod commonmark;
+mod html;
+mod markdown;
+
+impl Text {
+    /// Converts a text formatted using CommonMark.
+    ///
+    /// Headings are converted to bold text, lists to bulleted or numbered lines,
+    /// tables to lines of cells separated by `|`, code blocks to [`TextEntity::Pre`]
+    /// and block quotes to [`TextEntity::Blockquote`].
+    /// Line breaks inside a paragraph are kept as is.
+    ///
+    /// Constructs not supported by Telegram, such as images, HTML and relative links,
+    /// are converted to plain text, so the conversion never fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The text to convert.
+    #[cfg(feature = "commonmark")]
+    #[cfg_attr(nightly, doc(cfg(feature = "commonmark")))]
+    pub fn from_commonmark(input: &str) -> Self {
+        commonmark::parse(input)
+    }
+
+    /// Parses a text formatted using HTML.
+    ///
+    /// Follows the rules of [`crate::types::ParseMode::Html`],
+    /// so the result matches the text Telegram gets after parsing,
+    /// and an error indicates that Telegram would reject the markup.
+    ///
+    /// A link to `tg://user?id=` is parsed as [`TextEntity::TextLink`],
+    /// since the user is not known.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The text to parse.
+    pub fn from_html(input: &str) -> Result<Self, TextParseError> {
+        html::parse(input)
+    }
+
+    /// Parses a text formatted using MarkdownV2.
+    ///
+    /// Follows the rules of [`crate::types::ParseMode::MarkdownV2`],
+    /// so the result matches the text Telegram gets after parsing,
+    /// and an error indicates that Telegram would reject the markup.
+    ///
+    /// A link to `tg://user?id=` is parsed as [`TextEntity::TextLink`],
+    /// since the user is not known.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The text to parse.
+    pub fn from_markdown_v2(input: &str) -> Result<Self, TextParseError> {
+        markdown::parse(input)
+    }
+}
+
+/// Represents an error occurred when parsing a formatted text.
+///
+/// Offsets are in bytes of the input.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextParseError {
+    /// A custom emoji has an invalid URL.
+    InvalidCustomEmoji {
+        /// Offset of the URL.
+        offset: usize,
+    },
+    /// A required attribute of a tag is missing.
+    MissingAttribute {
+        /// The name of the tag.
+        tag: String,
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// Offset of the tag.
+        offset: usize,
+    },
+    /// A link has no URL.
+    MissingUrl {
+        /// Offset of the link text end.
+        offset: usize,
+    },
+    /// An entity is not closed.
+    UnclosedEntity {
+        /// The name of the entity.
+        name: String,
+        /// Offset of the entity start.
+        offset: usize,
+    },
+    /// A tag has no closing `>`.
+    UnclosedTag {
+        /// Offset of the tag.
+        offset: usize,
+    },
+    /// An end tag does not match the last start tag.
+    UnexpectedEndTag {
+        /// The name of the tag.
+        tag: String,
+        /// Offset of the tag.
+        offset: usize,
+    },
+    /// A reserved character is not escaped.
+    UnescapedCharacter {
+        /// The character.
+        character: char,
+        /// Offset of the character.
+        offset: usize,
+    },
+    /// A tag is not supported.
+    UnsupportedTag {
+        /// The name of the tag.
+        tag: String,
+        /// Offset of the tag.
+        offset: usize,
+    },
+}
+
+impl TextParseError {
+    /// Returns the offset of the error in bytes of the input.
+    pub fn offset(&self) -> usize {
+        use self::TextParseError::*;
+        match self {
+            InvalidCustomEmoji { offset }
+            | MissingAttribute { offset, .. }
+            | MissingUrl { offset }
+            | UnclosedEntity { offset, .. }
+            | UnclosedTag { offset }
+            | UnexpectedEndTag { offset, .. }
+            | UnescapedCharacter { offset, .. }
+            | UnsupportedTag { offset, .. } => *offset,
+        }
+    }
+}
+
+impl Error for TextParseError {}
+
+impl fmt::Display for TextParseError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        use self::TextParseError::*;
+        match self {
+            InvalidCustomEmoji { offset } => write!(out, "invalid custom emoji URL at byte offset {}", offset),
+            MissingAttribute { tag, attribute, offset } => write!(
+                out,
+                "tag \"{}\" must have attribute \"{}\" at byte offset {}",
+                tag, attribute, offset
+            ),
+            MissingUrl { offset } => write!(out, "can not find URL of the link at byte offset {}", offset),
+            UnclosedEntity { name, offset } => {
+                write!(out, "can not find end of {} entity at byte offset {}", name, offset)
+            }
+            UnclosedTag { offset } => write!(out, "can not find end of the tag at byte offset {}", offset),
+            UnexpectedEndTag { tag, offset } => write!(out, "unmatched end tag \"{}\" at byte offset {}", tag, offset),
+            UnescapedCharacter { character, offset } => write!(
+                out,
+                "character '{}' is reserved and must be escaped with the preceding '\\' at byte offset {}",
+                character, offset
+            ),
+            UnsupportedTag { tag, offset } => write!(out, "unsupported tag \"{}\" at byte offset {}", tag, offset),
+        }
+    }
+}
+
+#[derive(Default)]
+struct TextOutput {
+    data: String,
+    len: u32,
+    entities: Vec<(usize, TextEntity)>,
+}
+
+impl TextOutput {
+    fn push(&mut self, value: char) {
+        self.data.push(value);
+        self.len += value.len_utf16() as u32;
+    }
+
+    fn push_entity<F>(&mut self, order: usize, start: u32, entity: F)
+    where
+        F: FnOnce(TextEntityPosition) -> TextEntity,
+    {
+        if self.len > start {
+            self.entities.push((order, entity((start..self.len).into())));
+        }
+    }
+
+    fn finish(mut self) -> Text {
+        self.entities.sort_by_key(|(order, entity)| {
+            let position = entity.get_position();
+            (position.offset, Reverse(position.length), *order)
+        });
+        Text {
+            data: self.data,
+            entities: if self.entities.is_empty() {
+                None
+            } else {
+                Some(self.entities.into_iter().map(|(_, entity)| entity).collect())
+            },
+        }
+    }
+}
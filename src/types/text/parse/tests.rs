@@ -0,0 +1,249 @@
+use crate::types::{Text, TextBuilder, TextEntity, TextParseError};
+
+fn create_text() -> Text {
+    TextBuilder::default()
+        .plain("<a&b> ")
+        .bold(TextBuilder::default().plain("b ").italic("i"))
+        .plain(" ")
+        .underline(TextBuilder::default().italic("ui"))
+        .strikethrough("s")
+        .spoiler("sp")
+        .plain(" ")
+        .code("c`\\<")
+        .pre("fn main() {}", Some("rust"))
+        .pre("1_2\n", None)
+        .text_link("l", "https://example.com/?a=1&b=(2)")
+        .custom_emoji("👍", "5368324170671202286")
+        .plain("\n")
+        .blockquote(TextBuilder::default().plain("a\n").bold("b\nc"))
+        .plain("\n")
+        .expandable_blockquote("d\ne.")
+        .plain("\nf")
+        .build()
+}
+
+#[test]
+fn round_trip() {
+    let text = create_text();
+    assert_eq!(Text::from_html(&text.to_html()).unwrap(), text);
+    assert_eq!(Text::from_markdown_v2(&text.to_markdown_v2()).unwrap(), text);
+}
+
+#[test]
+fn from_html() {
+    let text = Text::from_html(concat!(
+        "<strong>b</strong><em>i</em><ins>u</ins><del>s</del><strike>s</strike>",
+        "<span class=\"tg-spoiler\">sp</span> ",
+        "<a href='https://example.com'>l</a> <A HREF=tg://user?id=1>m</A> ",
+        "<pre><code class=\"language-rust\">r</code></pre><pre>p<code>c</code></pre> ",
+        "&lt;&gt;&amp;&quot;&#65;&#x42;&unknown; &copy"
+    ))
+    .unwrap();
+    assert_eq!(text.data, "biusssp l m rpc <>&\"AB&unknown; &copy");
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![
+            TextEntity::bold(0..1),
+            TextEntity::italic(1..2),
+            TextEntity::underline(2..3),
+            TextEntity::strikethrough(3..4),
+            TextEntity::strikethrough(4..5),
+            TextEntity::spoiler(5..7),
+            TextEntity::text_link(8..9, "https://example.com"),
+            TextEntity::text_link(10..11, "tg://user?id=1"),
+            TextEntity::pre(12..13, Some("rust")),
+            TextEntity::pre(13..15, None::<String>),
+            TextEntity::code(14..15),
+        ]
+    );
+}
+
+#[test]
+fn from_html_semicolons() {
+    let text = Text::from_html(concat!(
+        "<a href=\"https://e.com/?a=1;b=2\">x</a> ",
+        "<a href=\"https://e.com/ä?x=1;y\">y</a> ",
+        "a;b ä;&;&amp;;"
+    ))
+    .unwrap();
+    assert_eq!(text.data, "x y a;b ä;&;&;");
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![
+            TextEntity::text_link(0..1, "https://e.com/?a=1;b=2"),
+            TextEntity::text_link(2..3, "https://e.com/ä?x=1;y"),
+        ]
+    );
+}
+
+#[test]
+fn from_html_errors() {
+    for (input, error) in [
+        (
+            "a <b>b",
+            TextParseError::UnclosedEntity {
+                name: String::from("b"),
+                offset: 2,
+            },
+        ),
+        ("a <b", TextParseError::UnclosedTag { offset: 2 }),
+        (
+            "<b><i>a</b></i>",
+            TextParseError::UnexpectedEndTag {
+                tag: String::from("b"),
+                offset: 7,
+            },
+        ),
+        (
+            "<a>a</a>",
+            TextParseError::MissingAttribute {
+                tag: String::from("a"),
+                attribute: "href",
+                offset: 0,
+            },
+        ),
+        (
+            "<tg-emoji>a</tg-emoji>",
+            TextParseError::MissingAttribute {
+                tag: String::from("tg-emoji"),
+                attribute: "emoji-id",
+                offset: 0,
+            },
+        ),
+        (
+            "ё<span>a</span>",
+            TextParseError::UnsupportedTag {
+                tag: String::from("span"),
+                offset: 2,
+            },
+        ),
+    ] {
+        let result = Text::from_html(input);
+        assert_eq!(result, Err(error.clone()), "{}", input);
+        assert_eq!(result.unwrap_err().offset(), error.offset());
+    }
+}
+
+#[test]
+fn from_markdown_v2() {
+    let text = Text::from_markdown_v2(concat!(
+        "___a_**__ \\*\\_ ||*sp*|| ",
+        "[l](tg://user?id=1) ![👍](tg://emoji?id=1) ",
+        "`a\\b` ```\ncode\n``` ```a b```\n",
+        ">q1\n>q2\nc\n**>e||"
+    ))
+    .unwrap();
+    assert_eq!(text.data, "a *_ sp l 👍 a\\b code a b\nq1\nq2\nc\ne");
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![
+            TextEntity::underline(0..1),
+            TextEntity::italic(0..1),
+            TextEntity::spoiler(5..7),
+            TextEntity::bold(5..7),
+            TextEntity::text_link(8..9, "tg://user?id=1"),
+            TextEntity::custom_emoji(10..12, "1"),
+            TextEntity::code(13..16),
+            TextEntity::pre(17..21, None::<String>),
+            TextEntity::pre(22..25, None::<String>),
+            TextEntity::blockquote(26..31),
+            TextEntity::expandable_blockquote(34..35),
+        ]
+    );
+}
+
+#[test]
+fn from_markdown_v2_errors() {
+    for (input, error) in [
+        (
+            "a.",
+            TextParseError::UnescapedCharacter {
+                character: '.',
+                offset: 1,
+            },
+        ),
+        (
+            "a|b",
+            TextParseError::UnescapedCharacter {
+                character: '|',
+                offset: 1,
+            },
+        ),
+        (
+            "a > b",
+            TextParseError::UnescapedCharacter {
+                character: '>',
+                offset: 2,
+            },
+        ),
+        (
+            "ё *a _b",
+            TextParseError::UnclosedEntity {
+                name: String::from("italic"),
+                offset: 6,
+            },
+        ),
+        (
+            "a `b",
+            TextParseError::UnclosedEntity {
+                name: String::from("code"),
+                offset: 2,
+            },
+        ),
+        ("[a] b", TextParseError::MissingUrl { offset: 2 }),
+        ("[a](b", TextParseError::MissingUrl { offset: 2 }),
+        (
+            "![a](tg://emoji?id=x)",
+            TextParseError::InvalidCustomEmoji { offset: 5 },
+        ),
+    ] {
+        let result = Text::from_markdown_v2(input);
+        assert_eq!(result, Err(error.clone()), "{}", input);
+        assert_eq!(result.unwrap_err().offset(), error.offset());
+    }
+}
+
+#[cfg(feature = "commonmark")]
+#[test]
+fn from_commonmark() {
+    let text = Text::from_commonmark(concat!(
+        "# Title\n\n",
+        "Some **bold**, *italic*, ~~deleted~~ and `code`.\nNext [link](https://example.com) ",
+        "[relative](/path) <https://example.org> ![image](https://example.com/a.png)\n\n",
+        "- a\n- b\n  1. c\n  2. d\n- [x] e\n\n",
+        "> quote\n>\n> > nested\n\n",
+        "```rust\nfn main() {}\n```\n\n",
+        "| x | y |\n|---|---|\n| 1 | 2 |\n\n",
+        "<div>html</div>\n\n",
+        "---\n\n",
+        "end"
+    ));
+    assert_eq!(
+        text.data,
+        concat!(
+            "Title\n\n",
+            "Some bold, italic, deleted and code.\nNext link relative https://example.org image\n\n",
+            "• a\n• b\n  1. c\n  2. d\n• ☑ e\n\n",
+            "quote\n\nnested\n\n",
+            "fn main() {}\n\n",
+            "x | y\n1 | 2\n\n",
+            "<div>html</div>\n\n",
+            "———\n\n",
+            "end"
+        )
+    );
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![
+            TextEntity::bold(0..5),
+            TextEntity::bold(12..16),
+            TextEntity::italic(18..24),
+            TextEntity::strikethrough(26..33),
+            TextEntity::code(38..42),
+            TextEntity::text_link(49..53, "https://example.com"),
+            TextEntity::blockquote(119..132),
+            TextEntity::pre(134..146, Some("rust")),
+            TextEntity::bold(148..153),
+        ]
+    );
+}
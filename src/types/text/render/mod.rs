@@ -0,0 +1,329 @@
+use std::fmt;
+
+use crate::types::{ParseMode, Text, TextEntity, ESCAPE_MARKDOWN_V2};
+
+#[cfg(test)]
+mod tests;
+
+impl Text {
+    /// Renders the text with entities as HTML.
+    ///
+    /// The result can be sent using [`ParseMode::Html`].
+    ///
+    /// Entities detected by Telegram automatically,
+    /// such as mentions, hashtags and URLs, are rendered as plain text.
+    pub fn to_html(&self) -> String {
+        render(self, HtmlRenderer::default())
+    }
+
+    /// Renders the text with entities as MarkdownV2.
+    ///
+    /// The result can be sent using [`ParseMode::MarkdownV2`].
+    ///
+    /// Entities detected by Telegram automatically,
+    /// such as mentions, hashtags and URLs, are rendered as plain text.
+    pub fn to_markdown_v2(&self) -> String {
+        render(self, MarkdownV2Renderer::default())
+    }
+}
+
+trait Renderer {
+    fn open(&mut self, entity: &TextEntity);
+
+    fn close(&mut self, entity: &TextEntity);
+
+    fn push_text(&mut self, value: &str);
+
+    fn finish(self) -> String;
+}
+
+struct OpenEntity<'a> {
+    entity: &'a TextEntity,
+    end: usize,
+}
+
+fn render<R>(text: &Text, mut renderer: R) -> String
+where
+    R: Renderer,
+{
+    let units: Vec<u16> = text.data.encode_utf16().collect();
+    let len = units.len();
+    let mut entities: Vec<(usize, usize, &TextEntity)> = text
+        .entities
+        .iter()
+        .flatten()
+        .filter(|entity| is_markup(entity))
+        .filter_map(|entity| {
+            let position = entity.get_position();
+            let start = position.offset as usize;
+            let end = (start + position.length as usize).min(len);
+            (start < end).then_some((start, end, entity))
+        })
+        .collect();
+    entities.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let mut boundaries: Vec<usize> = entities.iter().flat_map(|(start, end, _)| [*start, *end]).collect();
+    boundaries.push(len);
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut stack: Vec<OpenEntity> = Vec::new();
+    let mut next_entity = 0;
+    let mut position = 0;
+    for boundary in boundaries {
+        if boundary > position {
+            renderer.push_text(&String::from_utf16_lossy(&units[position..boundary]));
+            position = boundary;
+        }
+        if let Some(idx) = stack.iter().position(|x| x.end <= position) {
+            let mut reopen = Vec::new();
+            while stack.len() > idx {
+                let item = stack.pop().unwrap();
+                renderer.close(item.entity);
+                if item.end > position {
+                    reopen.push(item);
+                }
+            }
+            while let Some(item) = reopen.pop() {
+                renderer.open(item.entity);
+                stack.push(item);
+            }
+        }
+        while let Some((start, end, entity)) = entities.get(next_entity).copied() {
+            if start != position {
+                break;
+            }
+            next_entity += 1;
+            if stack.iter().any(|x| is_code(x.entity)) {
+                continue;
+            }
+            renderer.open(entity);
+            stack.push(OpenEntity { entity, end });
+        }
+    }
+    while let Some(item) = stack.pop() {
+        renderer.close(item.entity);
+    }
+    renderer.finish()
+}
+
+fn is_markup(entity: &TextEntity) -> bool {
+    use self::TextEntity::*;
+    !matches!(
+        entity,
+        BotCommand(_) | Cashtag(_) | Email(_) | Hashtag(_) | Mention(_) | PhoneNumber(_) | Url(_)
+    )
+}
+
+fn is_code(entity: &TextEntity) -> bool {
+    matches!(entity, TextEntity::Code(_) | TextEntity::Pre { .. })
+}
+
+fn get_mention_url(user_id: impl fmt::Display) -> String {
+    format!("tg://user?id={}", user_id)
+}
+
+#[derive(Default)]
+struct HtmlRenderer {
+    out: String,
+}
+
+impl HtmlRenderer {
+    fn push_attribute(&mut self, value: &str) {
+        self.out.push_str(&ParseMode::escape_html_attribute(value));
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn open(&mut self, entity: &TextEntity) {
+        use self::TextEntity::*;
+        match entity {
+            Blockquote(_) => self.out.push_str("<blockquote>"),
+            Bold(_) => self.out.push_str("<b>"),
+            Code(_) => self.out.push_str("<code>"),
+            CustomEmoji { custom_emoji_id, .. } => {
+                self.out.push_str("<tg-emoji emoji-id=\"");
+                self.push_attribute(custom_emoji_id);
+                self.out.push_str("\">");
+            }
+            ExpandableBlockquote(_) => self.out.push_str("<blockquote expandable>"),
+            Italic(_) => self.out.push_str("<i>"),
+            Pre { language: None, .. } => self.out.push_str("<pre>"),
+            Pre {
+                language: Some(language),
+                ..
+            } => {
+                self.out.push_str("<pre><code class=\"language-");
+                self.push_attribute(language);
+                self.out.push_str("\">");
+            }
+            Spoiler(_) => self.out.push_str("<tg-spoiler>"),
+            Strikethrough(_) => self.out.push_str("<s>"),
+            TextLink { url, .. } => {
+                self.out.push_str("<a href=\"");
+                self.push_attribute(url);
+                self.out.push_str("\">");
+            }
+            TextMention { user, .. } => {
+                self.out.push_str("<a href=\"");
+                self.push_attribute(&get_mention_url(user.id));
+                self.out.push_str("\">");
+            }
+            Underline(_) => self.out.push_str("<u>"),
+            BotCommand(_) | Cashtag(_) | Email(_) | Hashtag(_) | Mention(_) | PhoneNumber(_) | Url(_) => {}
+        }
+    }
+
+    fn close(&mut self, entity: &TextEntity) {
+        use self::TextEntity::*;
+        self.out.push_str(match entity {
+            Blockquote(_) | ExpandableBlockquote(_) => "</blockquote>",
+            Bold(_) => "</b>",
+            Code(_) => "</code>",
+            CustomEmoji { .. } => "</tg-emoji>",
+            Italic(_) => "</i>",
+            Pre { language: None, .. } => "</pre>",
+            Pre { language: Some(_), .. } => "</code></pre>",
+            Spoiler(_) => "</tg-spoiler>",
+            Strikethrough(_) => "</s>",
+            TextLink { .. } | TextMention { .. } => "</a>",
+            Underline(_) => "</u>",
+            BotCommand(_) | Cashtag(_) | Email(_) | Hashtag(_) | Mention(_) | PhoneNumber(_) | Url(_) => "",
+        })
+    }
+
+    fn push_text(&mut self, value: &str) {
+        self.out.push_str(&ParseMode::Html.escape(value));
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+#[derive(Default)]
+struct MarkdownV2Renderer {
+    out: String,
+    code_depth: usize,
+    quote_depth: usize,
+    is_quote_line_pending: bool,
+    after_underscore: bool,
+}
+
+impl MarkdownV2Renderer {
+    fn push_quote_prefix(&mut self) {
+        if self.is_quote_line_pending {
+            self.out.push('>');
+            self.is_quote_line_pending = false;
+        }
+    }
+
+    fn push_marker(&mut self, value: &str) {
+        self.push_quote_prefix();
+        // `__` is always treated as underline,
+        // so an empty bold entity separates adjacent italic and underline markers
+        if self.after_underscore && value.starts_with('_') {
+            self.out.push_str("**");
+        }
+        self.out.push_str(value);
+        self.after_underscore = value.ends_with('_');
+    }
+
+    fn push_url(&mut self, value: &str) {
+        self.out.push_str("](");
+        self.out.push_str(&ParseMode::MarkdownV2.escape_link_url(value));
+        self.out.push(')');
+        self.after_underscore = false;
+    }
+}
+
+impl Renderer for MarkdownV2Renderer {
+    fn open(&mut self, entity: &TextEntity) {
+        use self::TextEntity::*;
+        match entity {
+            Blockquote(_) => {
+                self.quote_depth += 1;
+                self.push_marker(">");
+            }
+            Bold(_) => self.push_marker("*"),
+            Code(_) => {
+                self.code_depth += 1;
+                self.push_marker("`");
+            }
+            CustomEmoji { .. } => self.push_marker("!["),
+            ExpandableBlockquote(_) => {
+                self.quote_depth += 1;
+                self.push_marker("**>");
+            }
+            Italic(_) => self.push_marker("_"),
+            Pre { language, .. } => {
+                self.code_depth += 1;
+                self.push_marker("```");
+                if let Some(language) = language {
+                    self.out.push_str(language);
+                }
+                self.out.push('\n');
+            }
+            Spoiler(_) => self.push_marker("||"),
+            Strikethrough(_) => self.push_marker("~"),
+            TextLink { .. } | TextMention { .. } => self.push_marker("["),
+            Underline(_) => self.push_marker("__"),
+            BotCommand(_) | Cashtag(_) | Email(_) | Hashtag(_) | Mention(_) | PhoneNumber(_) | Url(_) => {}
+        }
+    }
+
+    fn close(&mut self, entity: &TextEntity) {
+        use self::TextEntity::*;
+        match entity {
+            Blockquote(_) => {
+                self.quote_depth -= 1;
+                self.is_quote_line_pending = false;
+            }
+            Bold(_) => self.push_marker("*"),
+            Code(_) => {
+                self.code_depth -= 1;
+                self.push_marker("`");
+            }
+            CustomEmoji { custom_emoji_id, .. } => self.push_url(&format!("tg://emoji?id={}", custom_emoji_id)),
+            ExpandableBlockquote(_) => {
+                self.quote_depth -= 1;
+                self.is_quote_line_pending = false;
+                self.push_marker("||");
+            }
+            Italic(_) => self.push_marker("_"),
+            Pre { .. } => {
+                self.code_depth -= 1;
+                self.push_marker("\n```");
+            }
+            Spoiler(_) => self.push_marker("||"),
+            Strikethrough(_) => self.push_marker("~"),
+            TextLink { url, .. } => self.push_url(url),
+            TextMention { user, .. } => self.push_url(&get_mention_url(user.id)),
+            Underline(_) => self.push_marker("__"),
+            BotCommand(_) | Cashtag(_) | Email(_) | Hashtag(_) | Mention(_) | PhoneNumber(_) | Url(_) => {}
+        }
+    }
+
+    fn push_text(&mut self, value: &str) {
+        for c in value.chars() {
+            self.push_quote_prefix();
+            let is_special = if self.code_depth > 0 {
+                c == '`' || c == '\\'
+            } else {
+                c == '\\' || ESCAPE_MARKDOWN_V2.contains(&c)
+            };
+            if is_special {
+                self.out.push('\\');
+            }
+            self.out.push(c);
+            if c == '\n' && self.quote_depth > 0 {
+                self.is_quote_line_pending = true;
+            }
+        }
+        self.after_underscore = false;
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
@@ -0,0 +1,104 @@
+use crate::types::{Text, TextBuilder, TextEntity, User};
+
+fn create_text() -> Text {
+    TextBuilder::default()
+        .plain("<a&b> ")
+        .bold(TextBuilder::default().plain("b ").italic("i"))
+        .plain(" ")
+        .underline("u")
+        .strikethrough("s")
+        .spoiler("sp")
+        .plain(" ")
+        .code("c`\\<")
+        .pre("fn main() {}", Some("rust"))
+        .pre("1_2", None)
+        .text_link("l", "https://example.com/?a=1&b=(2)")
+        .text_mention("tm", User::new(1, "test", false))
+        .custom_emoji("👍", "5368324170671202286")
+        .plain("\n")
+        .build()
+}
+
+#[test]
+fn to_html() {
+    assert_eq!(
+        create_text().to_html(),
+        concat!(
+            "&lt;a&amp;b&gt; <b>b <i>i</i></b> <u>u</u><s>s</s><tg-spoiler>sp</tg-spoiler> ",
+            "<code>c`\\&lt;</code>",
+            "<pre><code class=\"language-rust\">fn main() {}</code></pre>",
+            "<pre>1_2</pre>",
+            "<a href=\"https://example.com/?a=1&amp;b=(2)\">l</a>",
+            "<a href=\"tg://user?id=1\">tm</a>",
+            "<tg-emoji emoji-id=\"5368324170671202286\">👍</tg-emoji>\n"
+        )
+    );
+}
+
+#[test]
+fn to_markdown_v2() {
+    assert_eq!(
+        create_text().to_markdown_v2(),
+        concat!(
+            "<a&b\\> *b _i_* __u__~s~||sp|| ",
+            "`c\\`\\\\<`",
+            "```rust\nfn main() {}\n```",
+            "```\n1_2\n```",
+            "[l](https://example.com/?a=1&b=(2\\))",
+            "[tm](tg://user?id=1)",
+            "![👍](tg://emoji?id=5368324170671202286)\n"
+        )
+    );
+}
+
+#[test]
+fn render_without_entities() {
+    let text = Text::from("a_b <c> \\");
+    assert_eq!(text.to_html(), "a_b &lt;c&gt; \\");
+    assert_eq!(text.to_markdown_v2(), "a\\_b <c\\> \\\\");
+}
+
+#[test]
+fn render_overlapping_entities() {
+    let text = Text::from("abcdef").with_entities(
+        vec![TextEntity::bold(0..4), TextEntity::italic(2..6)]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(text.to_html(), "<b>ab<i>cd</i></b><i>ef</i>");
+    assert_eq!(text.to_markdown_v2(), "*ab_cd_*_ef_");
+}
+
+#[test]
+fn render_nested_entities() {
+    let text = Text::from("🦀 a_b ё").with_entities(
+        vec![
+            TextEntity::italic(3..8),
+            TextEntity::underline(3..8),
+            TextEntity::bold(7..8),
+            TextEntity::mention(0..2),
+            TextEntity::code(3..6),
+            TextEntity::bold(4..5),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    assert_eq!(text.to_html(), "🦀 <i><u><code>a_b</code> <b>ё</b></u></i>");
+    assert_eq!(text.to_markdown_v2(), "🦀 _**__`a_b` *ё*__**_");
+}
+
+#[test]
+fn render_blockquote() {
+    let text = TextBuilder::default()
+        .plain("text\n")
+        .blockquote(TextBuilder::default().plain("a\n").bold("b\nc"))
+        .plain("\n")
+        .expandable_blockquote("d\ne.")
+        .plain("\nf")
+        .build();
+    assert_eq!(
+        text.to_html(),
+        "text\n<blockquote>a\n<b>b\nc</b></blockquote>\n<blockquote expandable>d\ne.</blockquote>\nf"
+    );
+    assert_eq!(text.to_markdown_v2(), "text\n>a\n>*b\n>c*\n**>d\n>e\\.||\nf");
+}
@@ -0,0 +1,109 @@
+use crate::types::{Text, TextEntity};
+
+#[cfg(test)]
+mod tests;
+
+impl Text {
+    /// Maximum length of a caption in UTF-16 code units.
+    pub const MAX_CAPTION_LEN: usize = 1024;
+
+    /// Maximum length of a message text in UTF-16 code units.
+    pub const MAX_MESSAGE_LEN: usize = 4096;
+
+    /// Splits the text into chunks of at most `limit` UTF-16 code units.
+    ///
+    /// A limit less than 2 is raised to 2, since a character may take two UTF-16 code units;
+    /// so chunks of `split(1)` are up to 2 units long.
+    ///
+    /// A chunk ends at the last paragraph break, line break or space within the limit,
+    /// in that order of preference; the separator itself is dropped.
+    /// When there is no such boundary, the text is cut at the limit,
+    /// but never inside a surrogate pair.
+    ///
+    /// Entities crossing a chunk boundary are clipped and continue in the next chunk.
+    /// Chunks consisting of whitespace only are skipped, since they can not be sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum length of a chunk;
+    ///             use [`Self::MAX_MESSAGE_LEN`] or [`Self::MAX_CAPTION_LEN`].
+    pub fn split(&self, limit: usize) -> Vec<Text> {
+        let limit = limit.max(2);
+        let units: Vec<u16> = self.data.encode_utf16().collect();
+        if units.len() <= limit {
+            return vec![self.clone()];
+        }
+        let mut result = Vec::new();
+        let mut start = 0;
+        while start < units.len() {
+            let (end, next) = find_boundary(&units, start, limit);
+            let chunk = self.slice(&units, start, end);
+            if !chunk.data.trim().is_empty() {
+                result.push(chunk);
+            }
+            start = next;
+        }
+        result
+    }
+
+    /// Returns a part of the text between UTF-16 offsets with entities clipped to it.
+    pub(crate) fn slice(&self, units: &[u16], start: usize, end: usize) -> Text {
+        let (start, end) = (start as u32, end as u32);
+        let entities: Vec<TextEntity> = self
+            .entities
+            .iter()
+            .flatten()
+            .filter_map(|entity| {
+                let position = entity.get_position();
+                let entity_start = position.offset.max(start);
+                let entity_end = (position.offset + position.length).min(end);
+                (entity_start < entity_end).then(|| {
+                    let mut entity = entity.clone();
+                    let position = entity.get_position_mut();
+                    position.offset = entity_start - start;
+                    position.length = entity_end - entity_start;
+                    entity
+                })
+            })
+            .collect();
+        Text {
+            data: String::from_utf16_lossy(&units[start as usize..end as usize]),
+            entities: if entities.is_empty() {
+                None
+            } else {
+                Some(entities.into_iter().collect())
+            },
+        }
+    }
+}
+
+/// Returns the end of a chunk starting at `start` and the start of the next chunk.
+fn find_boundary(units: &[u16], start: usize, limit: usize) -> (usize, usize) {
+    if units.len() - start <= limit {
+        return (units.len(), units.len());
+    }
+    let max_end = start + limit;
+    let window = start + 1..=max_end;
+    let is_newline = |idx: usize| units.get(idx) == Some(&u16::from(b'\n'));
+    if let Some(idx) = window.clone().rev().find(|idx| is_newline(*idx) && is_newline(idx + 1)) {
+        return (idx, idx + 2);
+    }
+    if let Some(idx) = window.clone().rev().find(|idx| is_newline(*idx)) {
+        return (idx, idx + 1);
+    }
+    if let Some(idx) = window.rev().find(|idx| units[*idx] == u16::from(b' ')) {
+        return (idx, idx + 1);
+    }
+    let mut end = max_end;
+    if is_high_surrogate(units[end - 1]) {
+        end -= 1;
+    }
+    if end == start {
+        end += 2;
+    }
+    (end, end)
+}
+
+fn is_high_surrogate(value: u16) -> bool {
+    (0xD800..0xDC00).contains(&value)
+}
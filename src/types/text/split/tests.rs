@@ -0,0 +1,80 @@
+use crate::types::{Text, TextEntity};
+
+fn create_text(data: &str, entities: Vec<TextEntity>) -> Text {
+    Text::from(data).with_entities(entities.into_iter().collect())
+}
+
+#[test]
+fn split_short() {
+    let text = create_text("text", vec![TextEntity::bold(0..4)]);
+    assert_eq!(text.split(4), vec![text]);
+}
+
+#[test]
+fn split_boundaries() {
+    let text = Text::from("ab cd\nef gh\n\nij kl");
+    assert_eq!(text.split(12), vec![Text::from("ab cd\nef gh"), Text::from("ij kl")]);
+    assert_eq!(
+        text.split(10),
+        vec![Text::from("ab cd"), Text::from("ef gh"), Text::from("ij kl")]
+    );
+    assert_eq!(
+        text.split(4),
+        vec![
+            Text::from("ab"),
+            Text::from("cd"),
+            Text::from("ef"),
+            Text::from("gh"),
+            Text::from("ij"),
+            Text::from("kl"),
+        ]
+    );
+    assert_eq!(
+        Text::from("ab\n \n\ncd").split(2),
+        vec![Text::from("ab"), Text::from("cd")]
+    );
+    assert_eq!(
+        Text::from("abcdefg").split(3),
+        vec![Text::from("abc"), Text::from("def"), Text::from("g")]
+    );
+}
+
+#[test]
+fn split_surrogate_pairs() {
+    assert_eq!(
+        Text::from("a🦀🦀").split(2),
+        vec![Text::from("a"), Text::from("🦀"), Text::from("🦀")]
+    );
+    assert_eq!(Text::from("🦀🦀").split(1), vec![Text::from("🦀"), Text::from("🦀")]);
+}
+
+#[test]
+fn split_small_limit() {
+    for limit in [0, 1] {
+        assert_eq!(
+            Text::from("abc🦀").split(limit),
+            vec![Text::from("ab"), Text::from("c"), Text::from("🦀")]
+        );
+    }
+    assert_eq!(Text::from("").split(0), vec![Text::from("")]);
+}
+
+#[test]
+fn split_entities() {
+    let text = create_text(
+        "bold text\nitalic",
+        vec![
+            TextEntity::bold(0..9),
+            TextEntity::italic(5..16),
+            TextEntity::code(10..12),
+        ],
+    );
+    assert_eq!(
+        text.split(8),
+        vec![
+            create_text("bold", vec![TextEntity::bold(0..4)]),
+            create_text("text", vec![TextEntity::bold(0..4), TextEntity::italic(0..4)]),
+            create_text("italic", vec![TextEntity::italic(0..6), TextEntity::code(0..2)]),
+        ]
+    );
+}
@@ -0,0 +1,354 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use crate::types::{Float, Integer, Text, TextBuilder, TextEntity, TextParseError, User};
+
+#[cfg(test)]
+mod tests;
+
+/// Represents a template producing a [`Text`] with entities.
+///
+/// A template is a text with entities containing placeholders, such as `{name}`;
+/// a name of a placeholder consists of ASCII letters, digits and `_`.
+/// Use `{{` and `}}` to get literal braces.
+///
+/// Placeholders are replaced with values when rendering,
+/// and entities of the template are extended to cover the values.
+/// Values are never parsed as markup, so user input can not break formatting.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::types::{TextEntity, TextTemplate, TextTemplateValue};
+///
+/// let template = TextTemplate::from_html("Hello, <b>{name}</b>! Your balance is {amount}.").unwrap();
+/// let text = template
+///     .render([
+///         ("name", TextTemplateValue::from("<John>")),
+///         ("amount", TextTemplateValue::Integer(10)),
+///     ])
+///     .unwrap();
+/// assert_eq!(text.data, "Hello, <John>! Your balance is 10.");
+/// let entities: Vec<TextEntity> = text.entities.unwrap().into();
+/// assert_eq!(entities, vec![TextEntity::bold(7..13)]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextTemplate {
+    text: Text,
+    slots: Vec<Slot>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Slot {
+    start: u32,
+    end: u32,
+    byte_start: usize,
+    byte_end: usize,
+    kind: SlotKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum SlotKind {
+    Literal(char),
+    Placeholder(String),
+}
+
+impl TextTemplate {
+    /// Creates a new `TextTemplate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A text with entities containing placeholders.
+    pub fn new<T>(text: T) -> Result<Self, TextTemplateError>
+    where
+        T: Into<Text>,
+    {
+        let text = text.into();
+        let slots = find_slots(&text.data)?;
+        Ok(Self { text, slots })
+    }
+
+    /// Creates a new `TextTemplate` from a text formatted using HTML.
+    ///
+    /// See [`Text::from_html`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A text formatted using HTML containing placeholders.
+    pub fn from_html(value: &str) -> Result<Self, TextTemplateError> {
+        Self::new(Text::from_html(value)?)
+    }
+
+    /// Returns names of placeholders in order of appearance.
+    pub fn get_placeholders(&self) -> Vec<&str> {
+        self.slots
+            .iter()
+            .filter_map(|slot| match slot.kind {
+                SlotKind::Placeholder(ref name) => Some(name.as_str()),
+                SlotKind::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Renders the template.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Values of placeholders.
+    pub fn render<I, K, V>(&self, values: I) -> Result<Text, TextTemplateError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<TextTemplateValue>,
+    {
+        let values: HashMap<String, TextTemplateValue> = values
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        let mut builder = TextBuilder::default();
+        let mut shifts = Vec::with_capacity(self.slots.len());
+        let mut last_idx = 0;
+        for slot in &self.slots {
+            builder = builder.plain(&self.text.data[last_idx..slot.byte_start]);
+            let start = builder.len_utf16();
+            builder = match slot.kind {
+                SlotKind::Literal(value) => builder.plain(String::from(value)),
+                SlotKind::Placeholder(ref name) => match values.get(name) {
+                    Some(value) => builder.plain(value.to_text()),
+                    None => return Err(TextTemplateError::MissingValue(name.clone())),
+                },
+            };
+            shifts.push(Shift {
+                slot,
+                start,
+                end: builder.len_utf16(),
+            });
+            last_idx = slot.byte_end;
+        }
+        let mut text = builder.plain(&self.text.data[last_idx..]).build();
+
+        let mut entities: Vec<TextEntity> = self
+            .text
+            .entities
+            .iter()
+            .flatten()
+            .filter_map(|entity| {
+                let position = entity.get_position();
+                let start = map_position(&shifts, position.offset, false);
+                let end = map_position(&shifts, position.offset + position.length, true);
+                (start < end).then(|| {
+                    let mut entity = entity.clone();
+                    let position = entity.get_position_mut();
+                    position.offset = start;
+                    position.length = end - start;
+                    entity
+                })
+            })
+            .collect();
+        entities.extend(text.entities.take().into_iter().flatten());
+        entities.sort_by(|a, b| {
+            let (a, b) = (a.get_position(), b.get_position());
+            a.offset.cmp(&b.offset).then(b.length.cmp(&a.length))
+        });
+        if !entities.is_empty() {
+            text.entities = Some(entities.into_iter().collect());
+        }
+        Ok(text)
+    }
+}
+
+struct Shift<'a> {
+    slot: &'a Slot,
+    start: u32,
+    end: u32,
+}
+
+/// Maps a position in the template to a position in the rendered text.
+///
+/// A position inside a placeholder is moved to the start of the value
+/// or to the end of the value when `is_end` is `true`.
+fn map_position(shifts: &[Shift], position: u32, is_end: bool) -> u32 {
+    let (mut old, mut new) = (0, 0);
+    for shift in shifts {
+        if position >= shift.slot.end {
+            (old, new) = (shift.slot.end, shift.end);
+        } else if position > shift.slot.start {
+            return if is_end { shift.end } else { shift.start };
+        } else {
+            break;
+        }
+    }
+    new + (position - old)
+}
+
+fn find_slots(data: &str) -> Result<Vec<Slot>, TextTemplateError> {
+    let mut result = Vec::new();
+    let mut chars = data.char_indices().peekable();
+    let mut position = 0;
+    while let Some((byte_start, c)) = chars.next() {
+        let start = position;
+        position += c.len_utf16() as u32;
+        let kind = match c {
+            '{' | '}' if chars.next_if(|(_, x)| *x == c).is_some() => {
+                position += 1;
+                SlotKind::Literal(c)
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) if !name.is_empty() => break,
+                        Some((_, x)) if x.is_ascii_alphanumeric() || x == '_' => name.push(x),
+                        _ => return Err(TextTemplateError::InvalidPlaceholder { offset: byte_start }),
+                    }
+                }
+                position += name.len() as u32 + 1;
+                SlotKind::Placeholder(name)
+            }
+            '}' => return Err(TextTemplateError::InvalidPlaceholder { offset: byte_start }),
+            _ => continue,
+        };
+        result.push(Slot {
+            start,
+            end: position,
+            byte_start,
+            byte_end: chars.peek().map(|(idx, _)| *idx).unwrap_or(data.len()),
+            kind,
+        });
+    }
+    Ok(result)
+}
+
+/// Represents a value of a placeholder in a [`TextTemplate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextTemplateValue {
+    /// A floating point number.
+    Float(Float),
+    /// A text with entities.
+    Formatted(Text),
+    /// An integer number.
+    Integer(Integer),
+    /// A text link.
+    Link {
+        /// The text of the link.
+        text: String,
+        /// The URL of the link.
+        url: String,
+    },
+    /// A mention of a user by the full name.
+    Mention(User),
+    /// A plain text.
+    Text(String),
+}
+
+impl TextTemplateValue {
+    /// Creates a new text link value.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the link.
+    /// * `url` - The URL of the link.
+    pub fn link<A, B>(text: A, url: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        Self::Link {
+            text: text.into(),
+            url: url.into(),
+        }
+    }
+
+    fn to_text(&self) -> Text {
+        match self {
+            Self::Float(value) => Text::from(value.to_string()),
+            Self::Formatted(value) => value.clone(),
+            Self::Integer(value) => Text::from(value.to_string()),
+            Self::Link { text, url } => TextBuilder::default().text_link(text.as_str(), url.as_str()).build(),
+            Self::Mention(user) => TextBuilder::default()
+                .text_mention(user.get_full_name(), user.clone())
+                .build(),
+            Self::Text(value) => Text::from(value.as_str()),
+        }
+    }
+}
+
+impl From<Float> for TextTemplateValue {
+    fn from(value: Float) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<Integer> for TextTemplateValue {
+    fn from(value: Integer) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<Text> for TextTemplateValue {
+    fn from(value: Text) -> Self {
+        Self::Formatted(value)
+    }
+}
+
+impl From<TextBuilder> for TextTemplateValue {
+    fn from(value: TextBuilder) -> Self {
+        Self::Formatted(value.build())
+    }
+}
+
+impl From<User> for TextTemplateValue {
+    fn from(value: User) -> Self {
+        Self::Mention(value)
+    }
+}
+
+impl From<String> for TextTemplateValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for TextTemplateValue {
+    fn from(value: &str) -> Self {
+        Self::Text(String::from(value))
+    }
+}
+
+/// Represents an error occurred when creating or rendering a [`TextTemplate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextTemplateError {
+    /// A placeholder is not closed, empty or contains an invalid character.
+    InvalidPlaceholder {
+        /// Offset of the placeholder in bytes of the template text.
+        offset: usize,
+    },
+    /// A value for a placeholder is not provided.
+    MissingValue(String),
+    /// Could not parse the template markup.
+    Parse(TextParseError),
+}
+
+impl From<TextParseError> for TextTemplateError {
+    fn from(err: TextParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl Error for TextTemplateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TextTemplateError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidPlaceholder { offset } => write!(out, "invalid placeholder at byte offset {}", offset),
+            Self::MissingValue(name) => write!(out, "missing value for placeholder \"{}\"", name),
+            Self::Parse(err) => write!(out, "can not parse template: {}", err),
+        }
+    }
+}
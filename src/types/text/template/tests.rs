@@ -0,0 +1,77 @@
+use crate::types::{
+    Text,
+    TextBuilder,
+    TextEntity,
+    TextParseError,
+    TextTemplate,
+    TextTemplateError,
+    TextTemplateValue,
+    User,
+};
+
+#[test]
+fn render() {
+    let template = TextTemplate::from_html(
+        "<i>🦀 {{<b>{name}</b>}}</i>, you have sent {amount} ({ratio}%) to {user} via {link}. {details}",
+    )
+    .unwrap();
+    assert_eq!(
+        template.get_placeholders(),
+        vec!["name", "amount", "ratio", "user", "link", "details"]
+    );
+    let user = User::new(1, "John", false).with_last_name("Doe");
+    let text = template
+        .render([
+            ("name", TextTemplateValue::from("<b>*Joh🦀n*</b>")),
+            ("amount", TextTemplateValue::Integer(10)),
+            ("ratio", TextTemplateValue::Float(2.5)),
+            ("user", TextTemplateValue::from(user.clone())),
+            ("link", TextTemplateValue::link("bank", "https://example.com")),
+            ("details", TextTemplateValue::from(TextBuilder::default().code("id"))),
+            ("unused", TextTemplateValue::from(String::from("value"))),
+        ])
+        .unwrap();
+    assert_eq!(
+        text.data,
+        "🦀 {<b>*Joh🦀n*</b>}, you have sent 10 (2.5%) to John Doe via bank. id"
+    );
+    assert_eq!(
+        text.entities.unwrap().into_iter().collect::<Vec<_>>(),
+        vec![
+            TextEntity::italic(0..20),
+            TextEntity::bold(4..19),
+            TextEntity::text_mention(49..57, user),
+            TextEntity::text_link(62..66, "https://example.com"),
+            TextEntity::code(68..70),
+        ]
+    );
+}
+
+#[test]
+fn render_without_entities() {
+    let template = TextTemplate::new("{a}{b}").unwrap();
+    assert_eq!(template.render([("a", "1"), ("b", "2")]).unwrap(), Text::from("12"));
+}
+
+#[test]
+fn errors() {
+    for (input, offset) in [("a {b", 2), ("a {}", 2), ("a {b c}", 2), ("a }", 2), ("ё {b-c}", 3)] {
+        assert_eq!(
+            TextTemplate::new(input),
+            Err(TextTemplateError::InvalidPlaceholder { offset }),
+            "{}",
+            input
+        );
+    }
+    assert_eq!(
+        TextTemplate::from_html("<b>{a}"),
+        Err(TextTemplateError::Parse(TextParseError::UnclosedEntity {
+            name: String::from("b"),
+            offset: 0
+        }))
+    );
+    assert_eq!(
+        TextTemplate::new("{a}").unwrap().render([("b", "1")]),
+        Err(TextTemplateError::MissingValue(String::from("a")))
+    );
+}
@@ -1,4 +1,4 @@
-use crate::types::{Message, Text};
+use crate::types::{Message, Text, TextEntity};
 
 #[test]
 fn get_bot_commands() {
@@ -31,3 +31,64 @@ fn traits() {
     assert_eq!(text, *"test");
     assert_eq!(text.as_ref(), "test");
 }
+
+#[test]
+fn entity_contents() {
+    let text = Text::from("🦀 @user #tag $USD https://example.com a@b.c +1234 link 👍").with_entities(
+        vec![
+            TextEntity::mention(3..8),
+            TextEntity::hashtag(9..13),
+            TextEntity::cashtag(14..18),
+            TextEntity::Url((19..38).into()),
+            TextEntity::email(39..44),
+            TextEntity::phone_number(45..50),
+            TextEntity::text_link(51..55, "https://example.org"),
+            TextEntity::custom_emoji(56..58, "1"),
+            TextEntity::bold(1..3),
+            TextEntity::bold(0..100),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    assert_eq!(
+        text.entity_contents()
+            .map(|(entity, content, range)| (entity.clone(), content, range))
+            .take(2)
+            .collect::<Vec<_>>(),
+        vec![
+            (TextEntity::mention(3..8), "@user", 5..10),
+            (TextEntity::hashtag(9..13), "#tag", 11..15),
+        ]
+    );
+    assert_eq!(text.entity_contents().count(), 8);
+    assert_eq!(text.mentions(), vec!["@user"]);
+    assert_eq!(text.hashtags(), vec!["#tag"]);
+    assert_eq!(text.cashtags(), vec!["$USD"]);
+    assert_eq!(text.urls(), vec!["https://example.com"]);
+    assert_eq!(text.emails(), vec!["a@b.c"]);
+    assert_eq!(text.phone_numbers(), vec!["+1234"]);
+    assert_eq!(text.text_links(), vec!["https://example.org"]);
+    assert_eq!(text.custom_emoji_ids(), vec!["1"]);
+    assert!(Text::from("text").urls().is_empty());
+}
+
+#[test]
+fn entity_contents_from_caption() {
+    let msg: Message = serde_json::from_value(serde_json::json!({
+        "message_id": 1, "date": 0,
+        "from": {"id": 1, "first_name": "firstname", "is_bot": false},
+        "chat": {"id": 1, "type": "supergroup", "title": "super-group-title"},
+        "caption": "photo #tag",
+        "caption_entities": [
+            {"type": "hashtag", "offset": 6, "length": 4}
+        ],
+        "photo": [{
+            "file_id": "photo-id",
+            "file_unique_id": "unique-id",
+            "width": 200,
+            "height": 200
+        }]
+    }))
+    .unwrap();
+    assert_eq!(msg.get_text().unwrap().hashtags(), vec!["#tag"]);
+}
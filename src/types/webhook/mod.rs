@@ -1,10 +1,13 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    io::Cursor,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    api::{Method, Payload},
-    types::{AllowedUpdate, Integer},
+    api::{Form, Method, Payload},
+    types::{AllowedUpdate, InputFile, InputFileReader, Integer},
 };
 
 #[cfg(test)]
@@ -196,7 +199,7 @@ pub struct SetWebhook {
     url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     allowed_updates: Option<HashSet<AllowedUpdate>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip)]
     certificate: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     drop_pending_updates: Option<bool>,
@@ -272,7 +275,11 @@ impl SetWebhook {
     ///
     /// # Arguments
     ///
-    /// * `value` - Public key certificate; so that the root certificate in use can be checked.
+    /// * `value` - PEM-encoded public key certificate;
+    ///   so that the root certificate in use can be checked.
+    ///
+    /// Required when a self-signed certificate is used.
+    /// The certificate is uploaded as a file, so the method is sent using `multipart/form-data`.
     pub fn with_certificate<T>(mut self, value: T) -> Self
     where
         T: Into<String>,
@@ -336,12 +343,39 @@ impl SetWebhook {
         self.secret_token = Some(value.into());
         self
     }
+
+    fn into_form(self, certificate: String) -> Form {
+        let mut form = Form::from([("url", self.url.into())]);
+        if let Some(value) = self.allowed_updates {
+            // serialization of a set of unit variants never fails
+            let value: BTreeSet<AllowedUpdate> = value.into_iter().collect();
+            form.insert_field("allowed_updates", serde_json::json!(value));
+        }
+        let certificate = InputFileReader::from(Cursor::new(certificate)).with_file_name("certificate.pem");
+        form.insert_field("certificate", InputFile::from(certificate));
+        if let Some(value) = self.drop_pending_updates {
+            form.insert_field("drop_pending_updates", value);
+        }
+        if let Some(value) = self.ip_address {
+            form.insert_field("ip_address", value);
+        }
+        if let Some(value) = self.max_connections {
+            form.insert_field("max_connections", value);
+        }
+        if let Some(value) = self.secret_token {
+            form.insert_field("secret_token", value);
+        }
+        form
+    }
 }
 
 impl Method for SetWebhook {
     type Response = bool;
 
-    fn into_payload(self) -> Payload {
-        Payload::json("setWebhook", self)
+    fn into_payload(mut self) -> Payload {
+        match self.certificate.take() {
+            Some(certificate) => Payload::form("setWebhook", self.into_form(certificate)),
+            None => Payload::json("setWebhook", self),
+        }
     }
 }
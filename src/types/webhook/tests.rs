@@ -1,8 +1,17 @@
 use std::collections::HashSet;
 
 use crate::{
-    api::{assert_payload_eq, Payload},
-    types::{tests::assert_json_eq, AllowedUpdate, DeleteWebhook, GetWebhookInfo, SetWebhook, WebhookInfo},
+    api::{assert_payload_eq, Form, FormValue, Payload},
+    types::{
+        tests::assert_json_eq,
+        AllowedUpdate,
+        DeleteWebhook,
+        GetWebhookInfo,
+        InputFile,
+        InputFileReader,
+        SetWebhook,
+        WebhookInfo,
+    },
 };
 
 #[test]
@@ -75,7 +84,6 @@ fn set_webhook() {
             "setWebhook",
             serde_json::json!({
                 "url": "url",
-                "certificate": "cert",
                 "ip_address": "127.0.0.1",
                 "max_connections": 10,
                 "drop_pending_updates": true,
@@ -84,7 +92,6 @@ fn set_webhook() {
             }),
         ),
         SetWebhook::new("url")
-            .with_certificate("cert")
             .with_ip_address("127.0.0.1")
             .with_max_connections(10)
             .with_allowed_updates(updates)
@@ -92,4 +99,24 @@ fn set_webhook() {
             .with_drop_pending_updates(true)
             .with_secret_token("secret-token"),
     );
+
+    let certificate = InputFileReader::from(std::io::Cursor::new("cert")).with_file_name("certificate.pem");
+    assert_payload_eq(
+        Payload::form(
+            "setWebhook",
+            Form::from([
+                ("url", FormValue::from("url")),
+                ("certificate", InputFile::from(certificate).into()),
+                ("max_connections", 10.into()),
+                ("drop_pending_updates", true.into()),
+                ("allowed_updates", r#"["callback_query","message"]"#.into()),
+            ]),
+        ),
+        SetWebhook::new("url")
+            .with_certificate("cert")
+            .with_max_connections(10)
+            .add_allowed_update(AllowedUpdate::Message)
+            .add_allowed_update(AllowedUpdate::CallbackQuery)
+            .with_drop_pending_updates(true),
+    );
 }
@@ -1,7 +1,11 @@
 #![allow(missing_docs)]
 use futures_util::stream::StreamExt;
-use mockito::Server;
-use tgbot::{api::Client, types::Close};
+use mockito::{Matcher, Server};
+use serde_json::json;
+use tgbot::{
+    api::Client,
+    types::{Close, SendMessage},
+};
 
 #[tokio::test]
 async fn execute() {
@@ -68,3 +72,44 @@ async fn download_file() {
         Err(err) => assert_eq!(err.to_string(), "failed to download file: status=400 text=test-error"),
     };
 }
+
+#[tokio::test]
+async fn send_message_thread() {
+    let mut server = Server::new_async().await;
+    let create_response = |message_id, text| {
+        json!({
+            "ok": true,
+            "result": {
+                "message_id": message_id,
+                "date": 0,
+                "chat": {"id": 1, "type": "private", "first_name": "test"},
+                "text": text
+            }
+        })
+        .to_string()
+    };
+    let first = "a".repeat(4000);
+    let second = "b".repeat(100);
+    let first_mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .match_body(Matcher::Json(json!({"chat_id": 1, "text": first})))
+        .with_body(create_response(1, &first))
+        .create();
+    let second_mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .match_body(Matcher::Json(json!({
+            "chat_id": 1,
+            "text": second,
+            "reply_parameters": {"message_id": 1}
+        })))
+        .with_body(create_response(2, &second))
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let messages = client
+        .send_message_thread(SendMessage::new(1, format!("{}\n{}", first, second)))
+        .await
+        .unwrap();
+    first_mock.assert();
+    second_mock.assert();
+    assert_eq!(messages.iter().map(|x| x.id).collect::<Vec<_>>(), vec![1, 2]);
+}
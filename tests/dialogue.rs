@@ -0,0 +1,283 @@
+#![allow(missing_docs)]
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tgbot::{
+    handler::{
+        Dialogue,
+        DialogueHandler,
+        DialogueKey,
+        DialogueRecord,
+        DialogueStorage,
+        DialogueTransition,
+        JsonFileDialogueStorage,
+        MemoryDialogueStorage,
+        UpdateHandler,
+    },
+    types::{Update, UpdateType},
+};
+use tokio::{sync::Mutex, time::sleep};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+enum State {
+    #[default]
+    Start,
+    ReceiveName,
+    ReceiveAge {
+        name: String,
+    },
+}
+
+type Events = Arc<Mutex<Vec<String>>>;
+
+struct Handler {
+    events: Events,
+}
+
+impl DialogueHandler<State> for Handler {
+    async fn handle(&self, state: State, update: Update) -> DialogueTransition<State> {
+        let text = update
+            .get_message()
+            .and_then(|x| x.get_text())
+            .map(|x| x.data.clone())
+            .unwrap_or_default();
+        self.events.lock().await.push(format!("{:?}: {}", state, text));
+        match state {
+            State::Start if text == "/register" => DialogueTransition::Next(State::ReceiveName),
+            State::Start => DialogueTransition::Stay,
+            State::ReceiveName => DialogueTransition::Next(State::ReceiveAge { name: text }),
+            State::ReceiveAge { .. } if text.parse::<u8>().is_err() => DialogueTransition::Stay,
+            State::ReceiveAge { .. } => DialogueTransition::Exit,
+        }
+    }
+
+    async fn handle_cancel(&self, state: State, _update: Update) {
+        self.events.lock().await.push(format!("cancel: {:?}", state));
+    }
+
+    async fn handle_timeout(&self, state: State, _update: &Update) {
+        self.events.lock().await.push(format!("timeout: {:?}", state));
+    }
+}
+
+fn create_update(id: i64, user_id: i64, thread_id: Option<i64>, text: &str) -> Update {
+    let mut value = json!({
+        "update_id": id,
+        "message": {
+            "message_id": id,
+            "date": 0,
+            "from": {"id": user_id, "is_bot": false, "first_name": "test"},
+            "chat": {"id": -1, "type": "supergroup", "title": "test", "is_forum": true},
+            "text": text
+        }
+    });
+    if text.starts_with('/') {
+        let length = text.split_whitespace().next().unwrap().encode_utf16().count();
+        value["message"]["entities"] = json!([{"type": "bot_command", "offset": 0, "length": length}]);
+    }
+    if let Some(thread_id) = thread_id {
+        value["message"]["message_thread_id"] = json!(thread_id);
+        value["message"]["is_topic_message"] = json!(true);
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+fn create_callback_query(id: i64, user_id: i64, message_text: &str) -> Update {
+    let length = message_text.encode_utf16().count();
+    serde_json::from_value(json!({
+        "update_id": id,
+        "callback_query": {
+            "id": "query-id",
+            "from": {"id": user_id, "is_bot": false, "first_name": "test"},
+            "chat_instance": "instance",
+            "data": "data",
+            "message": {
+                "message_id": id,
+                "date": 1,
+                "from": {"id": 100, "is_bot": true, "first_name": "bot"},
+                "chat": {"id": -1, "type": "supergroup", "title": "test", "is_forum": true},
+                "text": message_text,
+                "entities": [{"type": "bot_command", "offset": 0, "length": length}]
+            }
+        }
+    }))
+    .unwrap()
+}
+
+async fn take_events(events: &Events) -> Vec<String> {
+    std::mem::take(&mut *events.lock().await)
+}
+
+#[tokio::test]
+async fn dialogue() {
+    let events = Events::default();
+    let dialogue = Dialogue::new(
+        Handler { events: events.clone() },
+        MemoryDialogueStorage::<State>::default(),
+    );
+
+    dialogue.handle(create_update(1, 1, None, "hello")).await;
+    dialogue.handle(create_update(2, 1, None, "/register")).await;
+    dialogue.handle(create_update(3, 2, None, "hello")).await;
+    dialogue.handle(create_update(4, 1, Some(10), "hello")).await;
+    dialogue.handle(create_update(5, 1, None, "John")).await;
+    dialogue.handle(create_update(6, 1, None, "many")).await;
+    let key = DialogueKey::new(-1, 1);
+    assert_eq!(
+        dialogue.get_storage().get_state(key).await.unwrap().unwrap().state,
+        State::ReceiveAge {
+            name: String::from("John")
+        }
+    );
+    dialogue.handle(create_update(7, 1, None, "42")).await;
+    assert!(dialogue.get_storage().get_state(key).await.unwrap().is_none());
+
+    assert_eq!(
+        take_events(&events).await,
+        vec![
+            "Start: hello",
+            "Start: /register",
+            "Start: hello",
+            "Start: hello",
+            "ReceiveName: John",
+            "ReceiveAge { name: \"John\" }: many",
+            "ReceiveAge { name: \"John\" }: 42",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn dialogue_cancel() {
+    let events = Events::default();
+    let dialogue = Dialogue::new(
+        Handler { events: events.clone() },
+        MemoryDialogueStorage::<State>::default(),
+    )
+    .with_cancel_command("cancel")
+    .with_bot_username("my_bot");
+
+    dialogue.handle(create_update(1, 1, None, "/cancel")).await;
+    dialogue.handle(create_update(2, 1, None, "/register")).await;
+    dialogue.handle(create_update(3, 1, None, "/cancel@other_bot")).await;
+    dialogue.handle(create_update(4, 1, None, "/cancel@my_bot")).await;
+    dialogue.handle(create_update(5, 1, None, "hello")).await;
+
+    assert_eq!(
+        take_events(&events).await,
+        vec![
+            "Start: /cancel",
+            "Start: /register",
+            "ReceiveName: /cancel@other_bot",
+            "cancel: ReceiveAge { name: \"/cancel@other_bot\" }",
+            "Start: hello",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn dialogue_cancel_callback_query() {
+    let events = Events::default();
+    let dialogue = Dialogue::new(
+        Handler { events: events.clone() },
+        MemoryDialogueStorage::<State>::default(),
+    )
+    .with_cancel_command("cancel");
+
+    dialogue.handle(create_update(1, 1, None, "/register")).await;
+    dialogue.handle(create_callback_query(2, 1, "/cancel")).await;
+
+    assert_eq!(
+        take_events(&events).await,
+        vec!["Start: /register", "ReceiveName: /cancel"]
+    );
+    assert_eq!(
+        dialogue
+            .get_storage()
+            .get_state(DialogueKey::new(-1, 1))
+            .await
+            .unwrap()
+            .unwrap()
+            .state,
+        State::ReceiveAge {
+            name: String::from("/cancel")
+        }
+    );
+}
+
+#[test]
+fn dialogue_key_thread() {
+    let mut update = create_update(1, 1, Some(10), "hello");
+    assert_eq!(
+        DialogueKey::from_update(&update),
+        Some(DialogueKey::new(-1, 1).with_message_thread_id(10))
+    );
+    if let UpdateType::Message(ref mut message) = update.update_type {
+        message.is_topic_message = None;
+    }
+    assert_eq!(DialogueKey::from_update(&update), Some(DialogueKey::new(-1, 1)));
+}
+
+#[tokio::test]
+async fn dialogue_timeout() {
+    let events = Events::default();
+    let dialogue = Dialogue::new(
+        Handler { events: events.clone() },
+        MemoryDialogueStorage::<State>::default(),
+    )
+    .with_timeout(Duration::from_millis(100));
+
+    dialogue.handle(create_update(1, 1, None, "/register")).await;
+    dialogue.handle(create_update(2, 1, None, "John")).await;
+    sleep(Duration::from_millis(200)).await;
+    dialogue.handle(create_update(3, 1, None, "42")).await;
+
+    assert_eq!(
+        take_events(&events).await,
+        vec![
+            "Start: /register",
+            "ReceiveName: John",
+            "timeout: ReceiveAge { name: \"John\" }",
+            "Start: 42",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn dialogue_json_file_storage() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dialogues.json");
+    let key = DialogueKey::new(-1, 1).with_message_thread_id(10);
+
+    let storage = JsonFileDialogueStorage::<State>::new(&path);
+    assert!(storage.get_state(key).await.unwrap().is_none());
+    storage
+        .set_state(key, DialogueRecord::new(State::ReceiveName))
+        .await
+        .unwrap();
+    storage
+        .set_state(DialogueKey::new(-1, 2), DialogueRecord::new(State::Start))
+        .await
+        .unwrap();
+
+    let storage = JsonFileDialogueStorage::<State>::new(&path);
+    assert_eq!(storage.get_state(key).await.unwrap().unwrap().state, State::ReceiveName);
+    storage.remove_state(key).await.unwrap();
+    assert!(storage.get_state(key).await.unwrap().is_none());
+    assert!(storage.get_state(DialogueKey::new(-1, 2)).await.unwrap().is_some());
+
+    let events = Events::default();
+    let dialogue = Dialogue::new(Handler { events: events.clone() }, storage);
+    dialogue.handle(create_update(1, 1, Some(10), "/register")).await;
+    dialogue.handle(create_update(2, 1, Some(10), "John")).await;
+    assert_eq!(
+        take_events(&events).await,
+        vec!["Start: /register", "ReceiveName: John"]
+    );
+
+    std::fs::write(&path, "[invalid").unwrap();
+    assert!(JsonFileDialogueStorage::<State>::new(&path)
+        .get_state(key)
+        .await
+        .is_err());
+}
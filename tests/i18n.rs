@@ -0,0 +1,115 @@
+#![allow(missing_docs)]
+use mockito::{Matcher, Server};
+use serde_json::json;
+use tempfile::tempdir;
+use tgbot::{api::Client, i18n::Localization};
+
+const EN: &str = r#"
+[bot]
+name = "Test bot"
+description = "Bot for tests"
+
+[commands]
+start = "Start the bot"
+help = "Show help"
+
+[greeting]
+hello = "Hello, <b>{name}</b>!"
+"#;
+
+const DE: &str = r#"
+[bot]
+name = "Testbot"
+
+[commands]
+start = "Bot starten"
+unknown = "Skipped"
+
+[greeting]
+hello = "Hallo, <b>{name}</b>!"
+"#;
+
+const PT_BR: &str = r#"
+[bot]
+name = "Bot de teste"
+"#;
+
+async fn create_localization() -> Localization {
+    let dir = tempdir().unwrap();
+    tokio::fs::write(dir.path().join("en.toml"), EN).await.unwrap();
+    tokio::fs::write(dir.path().join("de.toml"), DE).await.unwrap();
+    tokio::fs::write(dir.path().join("pt_BR.toml"), PT_BR).await.unwrap();
+    tokio::fs::write(dir.path().join("readme.txt"), "skipped")
+        .await
+        .unwrap();
+    Localization::load_dir(dir.path(), "en").await.unwrap()
+}
+
+#[tokio::test]
+async fn load_dir() {
+    let localization = create_localization().await;
+    assert_eq!(localization.get_languages(), vec!["de", "en", "pt-br"]);
+
+    let locale = localization.get_locale(Some("pt-BR"));
+    assert_eq!(locale.get_language(), Some("pt-br"));
+    assert_eq!(locale.get("bot.name"), Some("Bot de teste"));
+    assert_eq!(locale.get("bot.description"), Some("Bot for tests"));
+
+    let text = localization
+        .get_locale(Some("de"))
+        .render("greeting.hello", [("name", "<b>")])
+        .unwrap();
+    assert_eq!(text.data, "Hallo, <b>!");
+}
+
+#[tokio::test]
+async fn sync_bot_info() {
+    let localization = create_localization().await;
+    let mut server = Server::new_async().await;
+    let mut mocks = Vec::new();
+    for (method, body) in [
+        ("setMyName", json!({"name": "Test bot"})),
+        ("setMyDescription", json!({"description": "Bot for tests"})),
+        (
+            "setMyCommands",
+            json!({"scope": {"type": "default"}, "commands": [
+                {"command": "start", "description": "Start the bot"},
+                {"command": "help", "description": "Show help"}
+            ]}),
+        ),
+        ("setMyName", json!({"name": "Testbot", "language_code": "de"})),
+        ("setMyName", json!({"name": "Test bot", "language_code": "en"})),
+        (
+            "setMyDescription",
+            json!({"description": "Bot for tests", "language_code": "en"}),
+        ),
+        (
+            "setMyCommands",
+            json!({"scope": {"type": "default"}, "language_code": "de", "commands": [
+                {"command": "start", "description": "Bot starten"},
+                {"command": "help", "description": "Show help"}
+            ]}),
+        ),
+        (
+            "setMyCommands",
+            json!({"scope": {"type": "default"}, "language_code": "en", "commands": [
+                {"command": "start", "description": "Start the bot"},
+                {"command": "help", "description": "Show help"}
+            ]}),
+        ),
+    ] {
+        mocks.push(
+            server
+                .mock("POST", format!("/bot-token/{}", method).as_str())
+                .match_body(Matcher::Json(body))
+                .with_body(r#"{"ok": true, "result": true}"#)
+                .expect(1)
+                .create(),
+        );
+    }
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    localization.sync_bot_info(&client).await.unwrap();
+    for mock in mocks {
+        mock.assert();
+    }
+}
@@ -5,11 +5,12 @@ use std::{
 };
 
 use dotenvy::dotenv;
-use mockito::{Matcher, Server};
+use futures_util::StreamExt;
+use mockito::{Matcher, Mock, Server, ServerGuard};
 use serde_json::json;
 use tgbot::{
     api::Client,
-    handler::{LongPoll, UpdateHandler},
+    handler::{LongPoll, LongPollOptions, UpdateHandler, UpdateSource},
     types::Update,
 };
 use tokio::{spawn, sync::Mutex, time::sleep};
@@ -25,11 +26,30 @@ impl UpdateHandler for Handler {
     }
 }
 
+fn mock_webhook_info(server: &mut ServerGuard, url: &str) -> Mock {
+    server
+        .mock("GET", "/bot-token/getWebhookInfo")
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": true,
+                "result": {
+                    "url": url,
+                    "has_custom_certificate": false,
+                    "pending_update_count": 0
+                }
+            }))
+            .unwrap(),
+        )
+        .expect(1)
+        .create()
+}
+
 #[tokio::test]
 async fn longpoll() {
     dotenv().ok();
     env_logger::init();
     let mut server = Server::new_async().await;
+    mock_webhook_info(&mut server, "");
     server
         .mock("POST", "/bot-token/getUpdates")
         .match_body(Matcher::PartialJson(json!({
@@ -91,3 +111,109 @@ async fn longpoll() {
     poll.run().await;
     assert!(!updates.lock().await.is_empty())
 }
+
+#[tokio::test]
+async fn longpoll_stream() {
+    let mut server = Server::new_async().await;
+    mock_webhook_info(&mut server, "");
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": true,
+                "result": [
+                    {"update_id": 1, "unknown": {}},
+                    {"update_id": 2, "unknown": {}},
+                    {"update_id": 3, "unknown": {}}
+                ]
+            }))
+            .unwrap(),
+        )
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let handler = Handler {
+        updates: updates.clone(),
+    };
+    let stream = LongPoll::new(client, ()).into_stream().filter(|x| {
+        let id = x.id;
+        async move { id != 2 }
+    });
+    stream.take(2).run_handler(handler).await;
+    sleep(Duration::from_millis(50)).await;
+    let update_ids: Vec<_> = updates.lock().await.iter().map(|x| x.id).collect();
+    assert_eq!(update_ids, vec![1, 3]);
+}
+
+#[tokio::test]
+async fn longpoll_delete_webhook() {
+    let mut server = Server::new_async().await;
+    let webhook_info = mock_webhook_info(&mut server, "https://example.com/webhook");
+    let delete_webhook = server
+        .mock("POST", "/bot-token/deleteWebhook")
+        .match_body(Matcher::Json(json!({"drop_pending_updates": true})))
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .expect(1)
+        .create();
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .with_body(r#"{"ok": true, "result": [{"update_id": 1, "unknown": {}}]}"#)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let options = LongPollOptions::default()
+        .with_delete_webhook(true)
+        .with_drop_pending_updates(true);
+    let updates: Vec<_> = LongPoll::new(client, ())
+        .with_options(options)
+        .into_stream()
+        .take(1)
+        .collect()
+        .await;
+    assert_eq!(updates.len(), 1);
+    webhook_info.assert();
+    delete_webhook.assert();
+}
+
+#[tokio::test]
+async fn longpoll_webhook_conflict() {
+    let mut server = Server::new_async().await;
+    mock_webhook_info(&mut server, "");
+    let conflict = server
+        .mock("POST", "/bot-token/getUpdates")
+        .with_status(409)
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": false,
+                "error_code": 409,
+                "description": "Conflict: can't use getUpdates method while webhook is active; \
+                    use deleteWebhook to delete the webhook first"
+            }))
+            .unwrap(),
+        )
+        .expect(1)
+        .create();
+    let delete_webhook = server
+        .mock("GET", "/bot-token/deleteWebhook")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .expect(1)
+        .create();
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .with_body(r#"{"ok": true, "result": [{"update_id": 1, "unknown": {}}]}"#)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let options = LongPollOptions::default()
+        .with_delete_webhook(true)
+        .with_error_timeout(1);
+    let now = Instant::now();
+    let updates: Vec<_> = LongPoll::new(client, ())
+        .with_options(options)
+        .into_stream()
+        .take(1)
+        .collect()
+        .await;
+    assert_eq!(updates.len(), 1);
+    assert!(now.elapsed() >= Duration::from_secs(1));
+    conflict.assert();
+    delete_webhook.assert();
+}
@@ -0,0 +1,250 @@
+#![allow(missing_docs)]
+use std::{collections::HashSet, sync::Arc};
+
+use mockito::Server;
+use regex::Regex;
+use serde_json::{json, Value};
+use tgbot::{
+    api::Client,
+    handler::{
+        AdminFilter,
+        CallbackDataFilter,
+        ChatType,
+        ChatTypeFilter,
+        CommandFilter,
+        FnFilter,
+        MessageDataFilter,
+        Router,
+        TextFilter,
+        UpdateFilter,
+        UpdateHandler,
+        UpdateKindFilter,
+    },
+    types::{AllowedUpdate, MessageData, Update},
+};
+use tokio::sync::Mutex;
+
+type Calls = Arc<Mutex<Vec<(&'static str, i64)>>>;
+
+struct Handler {
+    name: &'static str,
+    calls: Calls,
+}
+
+impl Handler {
+    fn new(name: &'static str, calls: &Calls) -> Self {
+        Self {
+            name,
+            calls: calls.clone(),
+        }
+    }
+}
+
+impl UpdateHandler for Handler {
+    async fn handle(&self, update: Update) {
+        self.calls.lock().await.push((self.name, update.id));
+    }
+}
+
+fn create_message_update(id: i64, chat: Value, message: Value) -> Update {
+    let mut value = json!({
+        "update_id": id,
+        "message": {
+            "message_id": id,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": chat
+        }
+    });
+    for (key, item) in message.as_object().unwrap() {
+        value["message"][key] = item.clone();
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+fn private_chat() -> Value {
+    json!({"id": 1, "type": "private", "first_name": "test"})
+}
+
+fn group_chat() -> Value {
+    json!({"id": -1, "type": "group", "title": "test"})
+}
+
+fn create_text_update(id: i64, chat: Value, text: &str) -> Update {
+    let mut message = json!({"text": text});
+    if text.starts_with('/') {
+        let length = text.split_whitespace().next().unwrap().encode_utf16().count();
+        message["entities"] = json!([{"type": "bot_command", "offset": 0, "length": length}]);
+    }
+    create_message_update(id, chat, message)
+}
+
+fn create_callback_update(id: i64, data: &str) -> Update {
+    serde_json::from_value(json!({
+        "update_id": id,
+        "callback_query": {
+            "id": "query-id",
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat_instance": "instance",
+            "data": data
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn router() {
+    let calls = Calls::default();
+    let regex = Regex::new("^hello").unwrap();
+    let router = Router::default()
+        .with_fallthrough_route(
+            ChatTypeFilter::new([ChatType::Private]),
+            Handler::new("private", &calls),
+        )
+        .with_route(CommandFilter::new("/start"), Handler::new("start", &calls))
+        .with_route(
+            TextFilter::new(move |x| regex.is_match(x)),
+            Handler::new("hello", &calls),
+        )
+        .with_route(CallbackDataFilter::new("button:"), Handler::new("button", &calls))
+        .with_route(CommandFilter::new("start"), Handler::new("unreachable", &calls))
+        .with_fallback(Handler::new("fallback", &calls));
+
+    router.handle(create_text_update(1, private_chat(), "/start arg")).await;
+    router.handle(create_text_update(2, group_chat(), "/start")).await;
+    router.handle(create_text_update(3, group_chat(), "hello world")).await;
+    router
+        .handle(create_text_update(4, private_chat(), "world hello"))
+        .await;
+    router.handle(create_callback_update(5, "button:1")).await;
+    router.handle(create_callback_update(6, "other")).await;
+    router.handle(create_text_update(7, group_chat(), "/help")).await;
+
+    assert_eq!(
+        *calls.lock().await,
+        vec![
+            ("private", 1),
+            ("start", 1),
+            ("start", 2),
+            ("hello", 3),
+            ("private", 4),
+            ("fallback", 4),
+            ("button", 5),
+            ("fallback", 6),
+            ("fallback", 7),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn router_command_bot_username() {
+    let calls = Calls::default();
+    let router = Router::default()
+        .with_route(
+            CommandFilter::new("start").with_bot_username("@my_bot"),
+            Handler::new("start", &calls),
+        )
+        .with_fallback(Handler::new("fallback", &calls));
+
+    router.handle(create_text_update(1, group_chat(), "/start")).await;
+    router
+        .handle(create_text_update(2, group_chat(), "/start@My_Bot"))
+        .await;
+    router
+        .handle(create_text_update(3, group_chat(), "/start@other_bot"))
+        .await;
+    router.handle(create_text_update(4, group_chat(), "/help@my_bot")).await;
+
+    assert_eq!(
+        *calls.lock().await,
+        vec![("start", 1), ("start", 2), ("fallback", 3), ("fallback", 4)]
+    );
+}
+
+#[tokio::test]
+async fn router_filters() {
+    let calls = Calls::default();
+    let router = Router::default()
+        .with_route(
+            UpdateKindFilter::new([AllowedUpdate::Message])
+                .and(ChatTypeFilter::new([ChatType::Group]).not())
+                .and(MessageDataFilter::new(|x| matches!(x, MessageData::Text(_)))),
+            Handler::new("private_text", &calls),
+        )
+        .with_route(
+            CallbackDataFilter::new("a").or(FnFilter::new(|x: &Update| x.id == 3)),
+            Handler::new("a_or_3", &calls),
+        );
+
+    router.handle(create_text_update(1, private_chat(), "text")).await;
+    router.handle(create_text_update(2, group_chat(), "text")).await;
+    router.handle(create_text_update(3, group_chat(), "text")).await;
+    router.handle(create_callback_update(4, "abc")).await;
+    router.handle(create_callback_update(5, "bc")).await;
+
+    assert_eq!(
+        *calls.lock().await,
+        vec![("private_text", 1), ("a_or_3", 3), ("a_or_3", 4)]
+    );
+}
+
+#[test]
+fn router_allowed_updates() {
+    let calls = Calls::default();
+    let router = Router::default()
+        .with_route(CommandFilter::new("start"), Handler::new("start", &calls))
+        .with_route(CallbackDataFilter::new("a"), Handler::new("a", &calls))
+        .with_route(
+            UpdateKindFilter::new([AllowedUpdate::Message, AllowedUpdate::InlineQuery])
+                .and(UpdateKindFilter::new([AllowedUpdate::InlineQuery, AllowedUpdate::Poll])),
+            Handler::new("inline", &calls),
+        )
+        .with_fallback(Handler::new("fallback", &calls));
+    assert_eq!(
+        router.get_allowed_updates(),
+        HashSet::from([
+            AllowedUpdate::BusinessMessage,
+            AllowedUpdate::CallbackQuery,
+            AllowedUpdate::ChannelPost,
+            AllowedUpdate::EditedBusinessMessage,
+            AllowedUpdate::EditedChannelPost,
+            AllowedUpdate::EditedMessage,
+            AllowedUpdate::InlineQuery,
+            AllowedUpdate::Message,
+        ])
+    );
+
+    let router = router.with_route(
+        ChatTypeFilter::new([ChatType::Private]),
+        Handler::new("private", &calls),
+    );
+    assert_eq!(router.get_allowed_updates().len(), 23);
+}
+
+#[tokio::test]
+async fn router_admin_filter() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/bot-token/getChatMember")
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": true,
+                "result": {
+                    "status": "creator",
+                    "user": {"id": 1, "is_bot": false, "first_name": "test"},
+                    "is_anonymous": false
+                }
+            }))
+            .unwrap(),
+        )
+        .expect(1)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let calls = Calls::default();
+    let router = Router::default().with_route(AdminFilter::new(client), Handler::new("admin", &calls));
+
+    router.handle(create_text_update(1, group_chat(), "text")).await;
+    router.handle(create_callback_update(2, "data")).await;
+
+    assert_eq!(*calls.lock().await, vec![("admin", 1)]);
+}
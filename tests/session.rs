@@ -0,0 +1,175 @@
+#![allow(missing_docs)]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tgbot::{
+    handler::{
+        JsonFileSessionStorage,
+        MemorySessionStorage,
+        SessionError,
+        SessionHandler,
+        SessionKey,
+        SessionManager,
+        SessionStorage,
+        SessionUpdateHandler,
+        UpdateHandler,
+        UpdateSessions,
+    },
+    types::{ChatPeerId, Update, UserPeerId},
+};
+use tokio::time::sleep;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Settings {
+    language: String,
+}
+
+async fn check_storage<T>(sessions: SessionManager<T>)
+where
+    T: SessionStorage,
+{
+    let user_id = UserPeerId::from(1);
+    let chat_id = ChatPeerId::from(1);
+
+    assert_eq!(sessions.get::<_, Settings>(user_id).await.unwrap(), None);
+    let settings = Settings {
+        language: String::from("en"),
+    };
+    assert_eq!(
+        sessions
+            .update(user_id, |_: Option<Settings>| Some(settings.clone()))
+            .await
+            .unwrap(),
+        Some(settings.clone())
+    );
+    assert_eq!(sessions.get(user_id).await.unwrap(), Some(settings.clone()));
+    assert_eq!(sessions.get::<_, Settings>(chat_id).await.unwrap(), None);
+
+    let mut first = sessions.load(user_id).await.unwrap();
+    let mut second = sessions.load(user_id).await.unwrap();
+    assert_eq!(first.get_key(), SessionKey::User(user_id));
+    assert_eq!(first.get_version(), 1);
+    first
+        .set(&Settings {
+            language: String::from("de"),
+        })
+        .unwrap();
+    sessions.save(&mut first).await.unwrap();
+    assert_eq!(first.get_version(), 2);
+    second.remove();
+    assert!(matches!(
+        sessions.save(&mut second).await.unwrap_err(),
+        SessionError::Conflict(SessionKey::User(_))
+    ));
+    assert_eq!(
+        sessions.get::<_, Settings>(user_id).await.unwrap().unwrap().language,
+        "de"
+    );
+
+    sessions.remove(user_id).await.unwrap();
+    assert_eq!(sessions.get::<_, Settings>(user_id).await.unwrap(), None);
+    let mut session = sessions.load(user_id).await.unwrap();
+    assert_eq!(session.get_version(), 3);
+    session.set(&1).unwrap();
+    sessions.save(&mut session).await.unwrap();
+    assert!(sessions.get::<_, Settings>(user_id).await.is_err());
+
+    let mut session = sessions.load(chat_id).await.unwrap();
+    assert_eq!(session.get_version(), 0);
+    sessions.update(chat_id, |_: Option<u32>| Some(1)).await.unwrap();
+    sessions.remove(chat_id).await.unwrap();
+    session.set(&2).unwrap();
+    assert!(matches!(
+        sessions.save(&mut session).await.unwrap_err(),
+        SessionError::Conflict(SessionKey::Chat(_))
+    ));
+    assert_eq!(sessions.load(chat_id).await.unwrap().get_version(), 2);
+}
+
+#[tokio::test]
+async fn session_memory_storage() {
+    check_storage(SessionManager::new(MemorySessionStorage::default())).await;
+}
+
+#[tokio::test]
+async fn session_json_file_storage() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sessions.json");
+    check_storage(SessionManager::new(JsonFileSessionStorage::new(&path))).await;
+
+    let sessions = SessionManager::new(JsonFileSessionStorage::new(&path));
+    assert_eq!(sessions.get::<_, u32>(UserPeerId::from(1)).await.unwrap(), Some(1));
+    sessions
+        .update(UserPeerId::from(2), |_: Option<()>| Some(()))
+        .await
+        .unwrap();
+    assert_eq!(sessions.get::<_, ()>(UserPeerId::from(2)).await.unwrap(), Some(()));
+}
+
+#[tokio::test]
+async fn session_ttl() {
+    let sessions = SessionManager::new(MemorySessionStorage::default()).with_ttl(Duration::from_millis(100));
+    let user_id = UserPeerId::from(1);
+    sessions.update(user_id, |_: Option<u32>| Some(1)).await.unwrap();
+    assert_eq!(sessions.get::<_, u32>(user_id).await.unwrap(), Some(1));
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(sessions.get::<_, u32>(user_id).await.unwrap(), None);
+    let mut session = sessions.load(user_id).await.unwrap();
+    assert_eq!(session.get_version(), 1);
+    session.set(&2).unwrap();
+    sessions.save(&mut session).await.unwrap();
+    assert_eq!(sessions.get::<_, u32>(user_id).await.unwrap(), Some(2));
+
+    let mut session = sessions.load(user_id).await.unwrap();
+    sleep(Duration::from_millis(200)).await;
+    session.set(&3).unwrap();
+    sessions.save(&mut session).await.unwrap();
+    assert_eq!(session.get_version(), 3);
+    assert_eq!(sessions.get::<_, u32>(user_id).await.unwrap(), Some(3));
+}
+
+struct Handler;
+
+impl SessionUpdateHandler for Handler {
+    async fn handle(&self, _update: Update, sessions: &mut UpdateSessions) {
+        let session = sessions.get_user_session().unwrap();
+        let counter = session.get::<u32>().unwrap().unwrap_or(0);
+        session.set(&(counter + 1)).unwrap();
+        if let Some(session) = sessions.get_chat_session() {
+            session.remove();
+        }
+    }
+}
+
+fn create_update(id: i64, chat_id: i64) -> Update {
+    serde_json::from_value(json!({
+        "update_id": id,
+        "message": {
+            "message_id": id,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": {"id": chat_id, "type": "group", "title": "test"},
+            "text": "test"
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn session_handler() {
+    let sessions = SessionManager::new(MemorySessionStorage::default());
+    let chat_id = ChatPeerId::from(-1);
+    sessions.update(chat_id, |_: Option<u32>| Some(1)).await.unwrap();
+
+    let handler = SessionHandler::new(Handler, sessions.clone());
+    handler.handle(create_update(1, -1)).await;
+    handler.handle(create_update(2, -2)).await;
+
+    assert_eq!(sessions.get::<_, u32>(UserPeerId::from(1)).await.unwrap(), Some(2));
+    assert_eq!(sessions.get::<_, u32>(chat_id).await.unwrap(), None);
+    assert_eq!(
+        handler.get_manager().get::<_, u32>(ChatPeerId::from(-2)).await.unwrap(),
+        None
+    );
+}
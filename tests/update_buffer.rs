@@ -0,0 +1,140 @@
+#![allow(missing_docs)]
+use std::{sync::Arc, time::Duration};
+
+use serde_json::json;
+use tgbot::{
+    handler::{UpdateBuffer, UpdateHandler},
+    types::{Integer, Update},
+};
+use tokio::{join, sync::Mutex, time::sleep};
+
+#[derive(Clone)]
+struct Handler {
+    update_ids: Arc<Mutex<Vec<Integer>>>,
+}
+
+impl UpdateHandler for Handler {
+    async fn handle(&self, update: Update) {
+        let mut update_ids = self.update_ids.lock().await;
+        update_ids.push(update.id);
+    }
+}
+
+fn create_update(id: Integer) -> Update {
+    serde_json::from_value(json!({
+        "update_id": id,
+        "message": {
+            "message_id": id,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": {"id": 1, "type": "private", "first_name": "test"},
+            "text": "test"
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn update_buffer_duplicates() {
+    let update_ids = Arc::new(Mutex::new(Vec::new()));
+    let buffer = UpdateBuffer::new(Handler {
+        update_ids: update_ids.clone(),
+    });
+    for id in [1, 1, 2, 1, 2, 3] {
+        buffer.handle(create_update(id)).await;
+    }
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn update_buffer_reorder() {
+    let update_ids = Arc::new(Mutex::new(Vec::new()));
+    let buffer = UpdateBuffer::new(Handler {
+        update_ids: update_ids.clone(),
+    })
+    .with_reorder_timeout(Duration::from_millis(100));
+    buffer.handle(create_update(1)).await;
+    join!(
+        buffer.handle(create_update(4)),
+        buffer.handle(create_update(3)),
+        async {
+            sleep(Duration::from_millis(20)).await;
+            buffer.handle(create_update(2)).await;
+        }
+    );
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn update_buffer_reorder_timeout() {
+    let update_ids = Arc::new(Mutex::new(Vec::new()));
+    let buffer = UpdateBuffer::new(Handler {
+        update_ids: update_ids.clone(),
+    })
+    .with_reorder_timeout(Duration::from_millis(50));
+    buffer.handle(create_update(1)).await;
+    join!(buffer.handle(create_update(4)), buffer.handle(create_update(3)));
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 3, 4]);
+
+    buffer.handle(create_update(2)).await;
+    buffer.handle(create_update(5)).await;
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 3, 4, 2, 5]);
+}
+
+#[derive(Clone)]
+struct SlowHandler {
+    update_ids: Arc<Mutex<Vec<Integer>>>,
+}
+
+impl UpdateHandler for SlowHandler {
+    async fn handle(&self, update: Update) {
+        // earlier updates sleep longer, so concurrent handling would reverse the order
+        sleep(Duration::from_millis(50 - update.id as u64 * 10)).await;
+        self.update_ids.lock().await.push(update.id);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn update_buffer_sequential() {
+    let update_ids = Arc::new(Mutex::new(Vec::new()));
+    let buffer = UpdateBuffer::new(SlowHandler {
+        update_ids: update_ids.clone(),
+    })
+    .with_reorder_timeout(Duration::from_millis(100));
+    buffer.handle(create_update(1)).await;
+    buffer.handle(create_update(2)).await;
+    join!(buffer.handle(create_update(4)), buffer.handle(create_update(3)));
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 2, 3, 4]);
+}
+
+#[derive(Clone)]
+struct PanicHandler {
+    update_ids: Arc<Mutex<Vec<Integer>>>,
+}
+
+impl UpdateHandler for PanicHandler {
+    async fn handle(&self, update: Update) {
+        if update.id == 2 {
+            panic!("failed to handle update {}", update.id);
+        }
+        self.update_ids.lock().await.push(update.id);
+    }
+}
+
+#[tokio::test]
+async fn update_buffer_handler_panic() {
+    let update_ids = Arc::new(Mutex::new(Vec::new()));
+    let buffer = UpdateBuffer::new(PanicHandler {
+        update_ids: update_ids.clone(),
+    });
+    for id in [1, 2, 3] {
+        buffer.handle(create_update(id)).await;
+    }
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(*update_ids.lock().await, vec![1, 3]);
+}